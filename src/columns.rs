@@ -0,0 +1,490 @@
+//! Сопоставление логических столбцов таблицы с индексами по тексту заголовков.
+
+use crate::diagnostics::ParseWarning;
+use crate::error::ReportError;
+use crate::grid::{GridRow, TableGrid};
+use crate::parse_config::ParseMode;
+use crate::types::Money;
+use crate::utils::{parse_date, parse_money_or_zero, parse_optional_date, parse_optional_time};
+use chrono::{NaiveDate, NaiveTime};
+
+/// Ожидаемый столбец таблицы.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnSpec {
+    /// Логическое имя столбца: по нему читаются значения и оно попадает в диагностику.
+    pub name: &'static str,
+    /// Фраза из заголовка верхнего уровня (для двухуровневых заголовков).
+    pub group: Option<&'static str>,
+    /// Начало текста заголовка столбца.
+    pub label: &'static str,
+    /// Без обязательного столбца таблицу разобрать нельзя.
+    pub required: bool,
+}
+
+impl ColumnSpec {
+    /// Обязательный столбец, имя которого совпадает с заголовком.
+    pub const fn new(label: &'static str) -> Self {
+        Self {
+            name: label,
+            group: None,
+            label,
+            required: true,
+        }
+    }
+
+    /// Обязательный столбец с логическим именем, отличным от текста заголовка.
+    pub const fn named(name: &'static str, label: &'static str) -> Self {
+        Self {
+            name,
+            group: None,
+            label,
+            required: true,
+        }
+    }
+
+    /// Обязательный столбец под заголовком верхнего уровня `group`.
+    pub const fn grouped(name: &'static str, group: &'static str, label: &'static str) -> Self {
+        Self {
+            name,
+            group: Some(group),
+            label,
+            required: true,
+        }
+    }
+
+    /// Помечает столбец необязательным: он есть не во всех вариантах отчёта.
+    pub const fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+}
+
+/// Заголовок одного столбца: тексты ячеек заголовка сверху вниз.
+#[derive(Debug)]
+struct HeaderColumn {
+    path: Vec<String>,
+}
+
+impl HeaderColumn {
+    fn label(&self) -> &str {
+        self.path.last().map_or("", String::as_str)
+    }
+
+    fn matches_group(&self, group: Option<&str>) -> bool {
+        let Some(group) = group else {
+            return true;
+        };
+        // Одноуровневый заголовок: группу различаем только порядком столбцов.
+        if self.path.len() < 2 {
+            return true;
+        }
+        let group = group.to_lowercase();
+        self.path[..self.path.len() - 1]
+            .iter()
+            .any(|parent| parent.to_lowercase().contains(&group))
+    }
+}
+
+/// Соответствие логических столбцов индексам ячеек в строках данных.
+#[derive(Debug)]
+pub struct ColumnMap {
+    table: &'static str,
+    specs: &'static [ColumnSpec],
+    indices: Vec<Option<usize>>,
+}
+
+impl ColumnMap {
+    /// Строит соответствие по строкам заголовка сетки таблицы.
+    ///
+    /// Для отсутствующих столбцов и лишних заголовков добавляются предупреждения.
+    pub fn resolve(
+        grid: &TableGrid,
+        table_name: &'static str,
+        specs: &'static [ColumnSpec],
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Self, ReportError> {
+        let columns: Vec<HeaderColumn> = grid
+            .header_paths()
+            .into_iter()
+            .map(|path| HeaderColumn { path })
+            .collect();
+        let mut claimed = vec![false; columns.len()];
+        let mut indices = Vec::with_capacity(specs.len());
+        let mut missing_required = None;
+
+        for spec in specs {
+            let label = normalize_header(spec.label);
+            let candidates = || {
+                columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, column)| !claimed[*idx] && column.matches_group(spec.group))
+            };
+            // Точное совпадение важнее префикса: «Дата» не должна занять «Дата приобретения».
+            let found = candidates()
+                .find(|(_, column)| normalize_header(column.label()) == label)
+                .or_else(|| {
+                    candidates()
+                        .find(|(_, column)| normalize_header(column.label()).starts_with(&label))
+                })
+                .map(|(idx, _)| idx);
+
+            match found {
+                Some(idx) => claimed[idx] = true,
+                None if spec.required => {
+                    if mode.is_strict() {
+                        return Err(ReportError::MissingColumn {
+                            table: table_name,
+                            column: spec.name,
+                        });
+                    }
+                    warnings.push(ParseWarning::MissingColumn {
+                        table: table_name,
+                        column: spec.name,
+                    });
+                    missing_required.get_or_insert(spec.name);
+                }
+                None => {}
+            }
+            indices.push(found);
+        }
+
+        for (column, _) in columns
+            .iter()
+            .zip(&claimed)
+            .filter(|(_, claimed)| !**claimed)
+        {
+            warnings.push(ParseWarning::UnexpectedColumn {
+                table: table_name,
+                header: column.path.join(" / "),
+            });
+        }
+
+        if let Some(column) = missing_required {
+            return Err(ReportError::MissingColumn {
+                table: table_name,
+                column,
+            });
+        }
+
+        Ok(Self {
+            table: table_name,
+            specs,
+            indices,
+        })
+    }
+
+    /// Минимальное число ячеек в строке, при котором доступны все обязательные столбцы.
+    pub fn min_cells(&self) -> usize {
+        self.specs
+            .iter()
+            .zip(&self.indices)
+            .filter(|(spec, _)| spec.required)
+            .filter_map(|(_, idx)| *idx)
+            .max()
+            .map_or(0, |idx| idx + 1)
+    }
+
+    /// Возвращает представление строки данных с доступом к ячейкам по имени столбца.
+    pub const fn row<'a>(&'a self, cells: &'a [String]) -> ColumnRow<'a> {
+        ColumnRow { map: self, cells }
+    }
+
+    /// Разбирает тело таблицы на строки данных, итоговые и служебные строки.
+    ///
+    /// Пустые строки пропускаются. Строки, для которых `is_marker` возвращает `true`
+    /// (разделители блоков, подзаголовки), отдаются как [`BodyRow::Marker`], строки
+    /// «Итого…» — как [`BodyRow::Total`]. Строка данных короче [`ColumnMap::min_cells`]
+    /// в строгом режиме — ошибка [`ReportError::MalformedRow`], в мягком — предупреждение,
+    /// и строка пропускается.
+    pub fn body_rows<'a>(
+        &'a self,
+        grid: &'a TableGrid,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+        is_marker: impl Fn(&GridRow) -> bool,
+    ) -> Result<Vec<BodyRow<'a>>, ReportError> {
+        let mut rows = Vec::new();
+        for (idx, grid_row) in grid.body_rows() {
+            let cells = grid_row.cells();
+            if grid_row.is_empty() {
+                continue;
+            }
+            if is_marker(grid_row) {
+                rows.push(BodyRow::Marker(cells));
+                continue;
+            }
+            if cells[0].to_lowercase().starts_with("итого") {
+                rows.push(BodyRow::Total(cells));
+                continue;
+            }
+            if cells.len() < self.min_cells() {
+                self.short_row(idx, cells.len(), mode, warnings)?;
+                continue;
+            }
+            rows.push(BodyRow::Data(idx, self.row(cells)));
+        }
+        Ok(rows)
+    }
+
+    /// Строки данных таблицы без служебных строк, с индексом строки в таблице.
+    ///
+    /// Правила отбора те же, что у [`ColumnMap::body_rows`]; итоговые строки пропускаются.
+    pub fn data_rows<'a>(
+        &'a self,
+        grid: &'a TableGrid,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Vec<(usize, ColumnRow<'a>)>, ReportError> {
+        Ok(self
+            .body_rows(grid, mode, warnings, |_| false)?
+            .into_iter()
+            .filter_map(|row| match row {
+                BodyRow::Data(idx, row) => Some((idx, row)),
+                BodyRow::Total(_) | BodyRow::Marker(_) => None,
+            })
+            .collect())
+    }
+
+    fn short_row(
+        &self,
+        row_index: usize,
+        actual_cells: usize,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<(), ReportError> {
+        if mode.is_strict() {
+            return Err(ReportError::MalformedRow {
+                table: self.table,
+                row_index,
+                expected_cells: self.min_cells(),
+                actual_cells,
+            });
+        }
+        warnings.push(ParseWarning::MalformedRow {
+            table: self.table,
+            row_index,
+            expected_cells: self.min_cells(),
+            actual_cells,
+        });
+        Ok(())
+    }
+
+    /// Индекс ячейки столбца; `None`, если необязательного столбца нет в таблице.
+    ///
+    /// Имя, не объявленное в описании таблицы, — ошибка парсера, а не отчёта.
+    fn index(&self, name: &'static str) -> Result<Option<usize>, ReportError> {
+        self.specs
+            .iter()
+            .position(|spec| spec.name == name)
+            .map(|position| self.indices[position])
+            .ok_or(ReportError::UnknownColumn {
+                table: self.table,
+                column: name,
+            })
+    }
+}
+
+/// Непустая строка тела таблицы, см. [`ColumnMap::body_rows`].
+#[derive(Debug, Clone, Copy)]
+pub enum BodyRow<'a> {
+    /// Строка данных и её индекс в таблице.
+    Data(usize, ColumnRow<'a>),
+    /// Итоговая строка: первая ячейка начинается с «Итого».
+    Total(&'a [String]),
+    /// Служебная строка, отобранная парсером секции.
+    Marker(&'a [String]),
+}
+
+/// Строка данных таблицы с доступом к ячейкам по логическому имени столбца.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnRow<'a> {
+    map: &'a ColumnMap,
+    cells: &'a [String],
+}
+
+impl<'a> ColumnRow<'a> {
+    /// Текст ячейки; пустая строка, если столбца нет в таблице.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает [`ReportError::UnknownColumn`], если имя не объявлено в описании таблицы.
+    pub fn text(&self, name: &'static str) -> Result<&'a str, ReportError> {
+        Ok(self
+            .map
+            .index(name)?
+            .and_then(|idx| self.cells.get(idx))
+            .map_or("", String::as_str))
+    }
+
+    /// Текст ячейки в виде владеющей строки.
+    pub fn string(&self, name: &'static str) -> Result<String, ReportError> {
+        self.text(name).map(str::to_string)
+    }
+
+    /// Денежное значение; пустая ячейка трактуется как ноль.
+    pub fn money(&self, name: &'static str) -> Result<Money, ReportError> {
+        parse_money_or_zero(self.text(name)?, name)
+    }
+
+    /// Дата в формате `dd.mm.yyyy`.
+    pub fn date(&self, name: &'static str) -> Result<NaiveDate, ReportError> {
+        parse_date(self.text(name)?)
+    }
+
+    /// Необязательная дата в формате `dd.mm.yyyy`.
+    pub fn optional_date(&self, name: &'static str) -> Result<Option<NaiveDate>, ReportError> {
+        parse_optional_date(self.text(name)?)
+    }
+
+    /// Необязательное время в формате `hh:mm:ss`.
+    pub fn optional_time(&self, name: &'static str) -> Result<Option<NaiveTime>, ReportError> {
+        parse_optional_time(self.text(name)?)
+    }
+}
+
+/// Приводит заголовок к виду для сравнения: нижний регистр, без сносок-индексов.
+fn normalize_header(text: &str) -> String {
+    text.chars()
+        .filter(|ch| {
+            !matches!(
+                ch,
+                '¹' | '²' | '³' | '⁴' | '⁵' | '⁶' | '⁷' | '⁸' | '⁹' | '⁰'
+            )
+        })
+        .collect::<String>()
+        .trim()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::{Html, Selector};
+
+    static SPECS: [ColumnSpec; 4] = [
+        ColumnSpec::new("Площадка"),
+        ColumnSpec::grouped("Количество начало", "Начало", "Количество"),
+        ColumnSpec::grouped("Количество конец", "Конец", "Количество"),
+        ColumnSpec::new("Остаток").optional(),
+    ];
+
+    fn resolve(html: &str) -> (Result<ColumnMap, ReportError>, Vec<ParseWarning>) {
+        let doc = Html::parse_document(html);
+        let table = doc
+            .select(&Selector::parse("table").expect("valid selector"))
+            .next()
+            .expect("table");
+        let grid = TableGrid::from_element(table);
+        let mut warnings = Vec::new();
+        let map = ColumnMap::resolve(&grid, "Test", &SPECS, ParseMode::Lenient, &mut warnings);
+        (map, warnings)
+    }
+
+    #[test]
+    fn resolves_two_level_header_with_spans() {
+        let (map, warnings) = resolve(
+            r#"<table>
+                <tr class="table-header"><td rowspan="2">Площадка</td><td>Прочее</td>
+                    <td colspan="2">Конец периода</td><td colspan="2">Начало периода</td></tr>
+                <tr class="table-header"><td>Код</td><td>Количество¹</td><td>Цена</td>
+                    <td>Количество, шт</td><td>Цена</td></tr>
+                <tr class="rn"><td>1</td></tr>
+                <tr><td>A</td><td>x</td><td>5</td><td>1</td><td>3</td><td>2</td></tr>
+            </table>"#,
+        );
+        let map = map.expect("resolved");
+        let cells: Vec<String> = ["A", "x", "5", "1", "3", "2"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let row = map.row(&cells);
+        assert_eq!(row.text("Количество начало").unwrap(), "3");
+        assert_eq!(row.text("Количество конец").unwrap(), "5");
+        assert_eq!(row.text("Остаток").unwrap(), "");
+        assert!(matches!(
+            row.text("Количество"),
+            Err(ReportError::UnknownColumn {
+                table: "Test",
+                column: "Количество",
+            })
+        ));
+        assert_eq!(map.min_cells(), 5);
+        assert_eq!(
+            warnings
+                .iter()
+                .filter(|warning| matches!(warning, ParseWarning::UnexpectedColumn { .. }))
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn reports_missing_required_column() {
+        let (map, warnings) = resolve(
+            r#"<table><tr class="table-header"><td>Площадка</td><td>Количество</td></tr></table>"#,
+        );
+        assert!(matches!(
+            map,
+            Err(ReportError::MissingColumn {
+                column: "Количество конец",
+                ..
+            })
+        ));
+        assert_eq!(
+            warnings,
+            [ParseWarning::MissingColumn {
+                table: "Test",
+                column: "Количество конец",
+            }]
+        );
+    }
+
+    #[test]
+    fn splits_body_into_data_total_and_marker_rows() {
+        let doc = Html::parse_document(
+            r#"<table>
+                <tr class="table-header"><td>Площадка</td><td>Количество</td>
+                    <td>Количество</td></tr>
+                <tr><td colspan="3">Основной рынок</td></tr>
+                <tr><td>A</td><td>1</td><td>2</td></tr>
+                <tr><td></td><td></td><td></td></tr>
+                <tr><td>B</td><td>1</td></tr>
+                <tr><td>Итого</td><td>1</td><td>2</td></tr>
+            </table>"#,
+        );
+        let table = doc
+            .select(&Selector::parse("table").expect("valid selector"))
+            .next()
+            .expect("table");
+        let grid = TableGrid::from_element(table);
+        let mut warnings = Vec::new();
+        let map = ColumnMap::resolve(&grid, "Test", &SPECS, ParseMode::Lenient, &mut warnings)
+            .expect("resolved");
+
+        let rows = map
+            .body_rows(
+                &grid,
+                ParseMode::Lenient,
+                &mut warnings,
+                GridRow::is_full_width,
+            )
+            .expect("lenient");
+        assert!(matches!(rows[0], BodyRow::Marker(cells) if cells[0] == "Основной рынок"));
+        assert!(matches!(rows[1], BodyRow::Data(2, row) if row.text("Площадка").unwrap() == "A"));
+        assert!(matches!(rows[2], BodyRow::Total(cells) if cells[0] == "Итого"));
+        assert_eq!(rows.len(), 3);
+        assert!(warnings.contains(&ParseWarning::MalformedRow {
+            table: "Test",
+            row_index: 4,
+            expected_cells: 3,
+            actual_cells: 2,
+        }));
+
+        assert!(matches!(
+            map.data_rows(&grid, ParseMode::Strict, &mut warnings),
+            Err(ReportError::MalformedRow { row_index: 4, .. })
+        ));
+    }
+}
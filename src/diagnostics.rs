@@ -1,6 +1,8 @@
 //! Диагностика мягкого парсинга: предупреждения, не приводящие к ошибке.
 
+use crate::encoding::TextEncoding;
 use crate::parse_config::ReportSection;
+use crate::types::SecurityKind;
 
 /// Предупреждение парсинга, которое фиксируется в мягком режиме.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,14 +25,102 @@ pub enum ParseWarning {
         /// Фактическое количество ячеек.
         actual_cells: usize,
     },
+    /// Значение ячейки не распознано; в мягком режиме поле получает вариант `Unknown`.
+    UnexpectedValue {
+        /// Имя таблицы.
+        table: &'static str,
+        /// Индекс строки внутри таблицы (0-based).
+        row_index: usize,
+        /// Название столбца.
+        column: &'static str,
+        /// Исходное значение.
+        value: String,
+    },
+    /// Источники классификации бумаги противоречат друг другу.
+    AmbiguousSecurityKind {
+        /// Имя таблицы с позицией.
+        table: &'static str,
+        /// ISIN бумаги.
+        isin: String,
+        /// Выбранный вид бумаги.
+        chosen: SecurityKind,
+        /// Все виды, предложенные справочником, названием и ISIN.
+        candidates: Vec<SecurityKind>,
+    },
+    /// В заголовке таблицы не найден ожидаемый столбец.
+    MissingColumn {
+        /// Имя таблицы.
+        table: &'static str,
+        /// Логическое имя столбца.
+        column: &'static str,
+    },
+    /// Запрошенная секция пропущена: в заголовке её таблицы нет обязательного столбца
+    /// (сам столбец описан отдельным предупреждением [`ParseWarning::MissingColumn`]).
+    SkippedSection {
+        /// Логическая секция, к которой относится таблица.
+        section: ReportSection,
+        /// Имя таблицы.
+        table: &'static str,
+        /// Логическое имя обязательного столбца.
+        column: &'static str,
+    },
+    /// В заголовке таблицы встретился столбец, которого парсер не ожидает.
+    UnexpectedColumn {
+        /// Имя таблицы.
+        table: &'static str,
+        /// Текст заголовка столбца (уровни через « / »).
+        header: String,
+    },
+    /// Таблица с заголовком не относится ни к одной известной секции отчёта.
+    UnrecognizedTable {
+        /// Заголовок раздела перед таблицей.
+        heading: Option<String>,
+        /// Тексты заголовков столбцов (уровни через « / »).
+        header_cells: Vec<String>,
+    },
+    /// Не разобрана секция, которая не была запрошена, но нужна для заполнения других
+    /// (справочник ЦБ для вида бумаг, блокировки для портфеля, внебиржевые операции для
+    /// стоимости приобретения); зависящие от неё поля остаются незаполненными.
+    DependencyFailed {
+        /// Вспомогательная секция.
+        section: ReportSection,
+        /// Текст ошибки разбора.
+        message: String,
+    },
+    /// Отчёт прочитан не из UTF-8 или с ошибками декодирования.
+    Transcoded {
+        /// Использованная кодировка и способ её определения.
+        encoding: TextEncoding,
+    },
 }
 
 impl ParseWarning {
     /// Возвращает имя таблицы, к которой относится предупреждение.
+    ///
+    /// Для предупреждений, не привязанных к разбираемой таблице (нераспознанная таблица,
+    /// вспомогательная секция, кодировка), возвращается пустая строка.
     #[must_use]
     pub const fn table(&self) -> &'static str {
         match self {
-            Self::MissingTable { table, .. } | Self::MalformedRow { table, .. } => table,
+            Self::MissingTable { table, .. }
+            | Self::MalformedRow { table, .. }
+            | Self::UnexpectedValue { table, .. }
+            | Self::AmbiguousSecurityKind { table, .. }
+            | Self::MissingColumn { table, .. }
+            | Self::SkippedSection { table, .. }
+            | Self::UnexpectedColumn { table, .. } => table,
+            Self::UnrecognizedTable { .. }
+            | Self::DependencyFailed { .. }
+            | Self::Transcoded { .. } => "",
+        }
+    }
+
+    /// Возвращает заголовок раздела нераспознанной таблицы, если он есть.
+    #[must_use]
+    pub fn heading(&self) -> Option<&str> {
+        match self {
+            Self::UnrecognizedTable { heading, .. } => heading.as_deref(),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,168 @@
+//! Определение кодировки исходного HTML: BOM, `<meta charset>`, эвристика по содержимому.
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use regex::bytes::Regex;
+use std::sync::LazyLock;
+
+// Объявление кодировки ищется только в начале документа (с запасом на длинный `<head>`).
+const META_SCAN_LIMIT: usize = 4096;
+
+static META_CHARSET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<meta[^>]*charset\s*=\s*["']?\s*([a-z0-9_:.\-]+)"#)
+        .expect("valid meta charset regex")
+});
+
+/// Откуда взята кодировка исходного отчёта.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingSource {
+    /// Метка порядка байтов (BOM) в начале файла.
+    Bom,
+    /// Байты образуют корректный UTF-8.
+    Utf8,
+    /// Объявление `<meta charset>` в заголовке документа.
+    MetaCharset,
+    /// Эвристика по содержимому.
+    Detected,
+    /// Кодировка указана вызывающим кодом.
+    Explicit,
+}
+
+/// Кодировка, в которой был прочитан отчёт.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEncoding {
+    /// Каноническое имя кодировки (например, `windows-1251`).
+    pub name: &'static str,
+    /// Способ определения кодировки.
+    pub source: EncodingSource,
+    /// При декодировании встретились некорректные последовательности (заменены на `U+FFFD`).
+    pub had_errors: bool,
+}
+
+impl TextEncoding {
+    /// Проверяет, что текст перекодирован не из UTF-8 или декодирован с ошибками.
+    #[must_use]
+    pub fn is_transcoded(&self) -> bool {
+        self.name != UTF_8.name() || self.had_errors
+    }
+}
+
+/// Декодирует байты отчёта, определяя кодировку.
+///
+/// Порядок проверок: BOM, корректный UTF-8, `<meta charset>` (если байты декодируются
+/// в объявленной кодировке без ошибок), эвристика. Корректный UTF-8 важнее объявления:
+/// пересохранённые отчёты часто сохраняют прежний `<meta>`.
+pub fn decode(bytes: &[u8]) -> (String, TextEncoding) {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        return decode_with(encoding, &bytes[bom_len..], EncodingSource::Bom);
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (
+            text.to_string(),
+            TextEncoding {
+                name: UTF_8.name(),
+                source: EncodingSource::Utf8,
+                had_errors: false,
+            },
+        );
+    }
+    if let Some(encoding) = meta_charset(bytes) {
+        let decoded = decode_with(encoding, bytes, EncodingSource::MetaCharset);
+        if !decoded.1.had_errors {
+            return decoded;
+        }
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    decode_with(detector.guess(None, false), bytes, EncodingSource::Detected)
+}
+
+/// Декодирует байты в кодировке с указанной меткой (`windows-1251`, `cp1251`, `koi8-r` и т. п.).
+///
+/// BOM удаляется, только если он соответствует указанной кодировке.
+pub fn decode_with_label(bytes: &[u8], label: &str) -> Option<(String, TextEncoding)> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())?;
+    let (text, had_errors) = encoding.decode_with_bom_removal(bytes);
+    Some((
+        text.into_owned(),
+        TextEncoding {
+            name: encoding.name(),
+            source: EncodingSource::Explicit,
+            had_errors,
+        },
+    ))
+}
+
+fn decode_with(
+    encoding: &'static Encoding,
+    bytes: &[u8],
+    source: EncodingSource,
+) -> (String, TextEncoding) {
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+    (
+        text.into_owned(),
+        TextEncoding {
+            name: encoding.name(),
+            source,
+            had_errors,
+        },
+    )
+}
+
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(META_SCAN_LIMIT)];
+    META_CHARSET_RE
+        .captures(head)
+        .and_then(|caps| Encoding::for_label(&caps[1]))
+        // Как в браузерах: объявленный в разметке UTF-16 читается как UTF-8.
+        .map(Encoding::output_encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{KOI8_R, WINDOWS_1251};
+
+    fn html(meta: &str) -> String {
+        format!(
+            "<html><head>{meta}</head><body><p>Оценка активов по состоянию на конец периода</p></body></html>"
+        )
+    }
+
+    #[test]
+    fn decodes_by_bom_and_valid_utf8() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(html("").as_bytes());
+        let (text, encoding) = decode(&bytes);
+        assert_eq!(text, html(""));
+        assert_eq!(encoding.source, EncodingSource::Bom);
+
+        // Объявление windows-1251 не перебивает корректный UTF-8.
+        let source = html(r#"<meta charset="windows-1251">"#);
+        let (text, encoding) = decode(source.as_bytes());
+        assert_eq!(text, source);
+        assert_eq!(encoding.source, EncodingSource::Utf8);
+        assert!(!encoding.is_transcoded());
+    }
+
+    #[test]
+    fn decodes_by_meta_charset_or_detection() {
+        let source =
+            html(r#"<meta http-equiv="Content-Type" content="text/html; charset=koi8-r">"#);
+        let (bytes, _, _) = KOI8_R.encode(&source);
+        let (text, encoding) = decode(&bytes);
+        assert_eq!(text, source);
+        assert_eq!(encoding.name, "KOI8-R");
+        assert_eq!(encoding.source, EncodingSource::MetaCharset);
+
+        // Объявление UTF-8 не соответствует байтам: кодировка определяется по содержимому.
+        let source = html(r#"<meta charset="UTF-8">"#);
+        let (bytes, _, _) = WINDOWS_1251.encode(&source);
+        let (text, encoding) = decode(&bytes);
+        assert_eq!(text, source);
+        assert_eq!(encoding.name, "windows-1251");
+        assert_eq!(encoding.source, EncodingSource::Detected);
+        assert!(encoding.is_transcoded());
+    }
+}
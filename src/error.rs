@@ -6,9 +6,27 @@ pub enum ReportError {
     /// Ошибка ввода-вывода при чтении исходного файла.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+    /// Метка кодировки не распознана.
+    #[error("Unknown encoding '{label}'")]
+    UnknownEncoding {
+        /// Переданная метка кодировки.
+        label: String,
+    },
     /// Ошибка парсинга HTML.
     #[error("HTML parsing error: {0}")]
     Html(String),
+    /// Ошибка чтения ZIP-архива.
+    #[error("Archive error: {0}")]
+    Archive(String),
+    /// Ошибка разбора почтового сообщения.
+    #[error("E-mail parsing error: {0}")]
+    Mail(String),
+    /// Ошибка чтения XLSX-книги.
+    #[error("Workbook error: {0}")]
+    Workbook(String),
+    /// Некорректный шаблон путей в настройках загрузки.
+    #[error("Invalid path pattern: {0}")]
+    Pattern(String),
     /// В отчёте не удалось найти ожидаемую таблицу.
     #[error("Table '{table}' not found")]
     TableNotFound {
@@ -35,6 +53,30 @@ pub enum ReportError {
         /// Имя пропавшего поля.
         field: &'static str,
     },
+    /// В заголовке таблицы не найден обязательный столбец.
+    #[error("Column '{column}' not found in table '{table}'")]
+    MissingColumn {
+        /// Имя таблицы.
+        table: &'static str,
+        /// Логическое имя столбца.
+        column: &'static str,
+    },
+    /// Парсер обратился к столбцу, которого нет в описании таблицы.
+    #[error("Column '{column}' is not declared for table '{table}'")]
+    UnknownColumn {
+        /// Имя таблицы.
+        table: &'static str,
+        /// Запрошенное логическое имя столбца.
+        column: &'static str,
+    },
+    /// Значение ячейки не входит в набор ожидаемых.
+    #[error("Unexpected value '{value}' in column '{column}'")]
+    UnexpectedValue {
+        /// Некорректное исходное значение.
+        value: String,
+        /// Название столбца.
+        column: &'static str,
+    },
     /// Не удалось сопоставить текст с ожидаемым форматом.
     #[error("Regex did not match: {0}")]
     Regex(String),
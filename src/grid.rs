@@ -0,0 +1,381 @@
+//! Нормализованная сетка HTML-таблицы с развёрнутыми `rowspan`/`colspan`.
+
+use crate::utils::collect_text;
+use scraper::{ElementRef, Selector};
+use std::sync::LazyLock;
+
+static ROW_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("tr").expect("valid tr selector"));
+static CELL_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("td, th").expect("valid cell selector"));
+
+/// Наибольший `colspan`, как в HTML: большие значения урезаются до него.
+const MAX_COLSPAN: usize = 1000;
+/// Наибольший `rowspan`, как в HTML: большие значения урезаются до него.
+const MAX_ROWSPAN: usize = 65534;
+
+/// Собственная ячейка строки до развёртывания объединений.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SpanCell {
+    pub(crate) text: String,
+    pub(crate) rowspan: usize,
+    pub(crate) colspan: usize,
+}
+
+/// Строка сетки таблицы.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridRow {
+    cells: Vec<String>,
+    class: Option<String>,
+    full_width: bool,
+}
+
+impl GridRow {
+    /// Тексты ячеек строки; объединённые ячейки повторяются в каждой занятой позиции.
+    #[must_use]
+    pub fn cells(&self) -> &[String] {
+        &self.cells
+    }
+
+    /// Текст ячейки по индексу столбца или пустая строка, если ячейки нет.
+    #[must_use]
+    pub fn cell(&self, idx: usize) -> &str {
+        self.cells.get(idx).map_or("", String::as_str)
+    }
+
+    /// Значение атрибута `class` у `tr`.
+    #[must_use]
+    pub fn class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+
+    /// Проверяет, что все ячейки строки пусты.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(String::is_empty)
+    }
+
+    /// Строка из одной собственной ячейки, `colspan` которой покрывает ширину таблицы:
+    /// подзаголовок блока или разделитель.
+    ///
+    /// Одиночная ячейка без `colspan` тоже считается подзаголовком, а строка с одной
+    /// собственной ячейкой и ячейками, перенесёнными `rowspan` сверху, — нет.
+    #[must_use]
+    pub const fn is_full_width(&self) -> bool {
+        self.full_width
+    }
+
+    fn has_class(&self, class: &str) -> bool {
+        self.class
+            .as_deref()
+            .is_some_and(|value| value.split_whitespace().any(|item| item == class))
+    }
+}
+
+/// Таблица отчёта, приведённая к сетке: объединённые ячейки развёрнуты по строкам и столбцам.
+///
+/// Строки заголовка — первые строки с классом `table-header` (или первая строка, если класс
+/// не проставлен). Строка нумерации столбцов (`rn`) после заголовка в тело не входит.
+/// Короткие строки не дополняются пустыми ячейками, чтобы их можно было диагностировать.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableGrid {
+    rows: Vec<GridRow>,
+    header_rows: usize,
+    body_start: usize,
+}
+
+impl TableGrid {
+    /// Строит сетку по элементу `table`.
+    pub(crate) fn from_element(table: ElementRef<'_>) -> Self {
+        Self::from_rows(table.select(&ROW_SELECTOR).map(|tr| {
+            let cells = tr
+                .select(&CELL_SELECTOR)
+                .map(|td| SpanCell {
+                    text: collect_text(td),
+                    rowspan: span(td, "rowspan"),
+                    colspan: span(td, "colspan"),
+                })
+                .collect();
+            (tr.value().attr("class").map(str::to_string), cells)
+        }))
+    }
+
+    /// Строит сетку по строкам собственных ячеек с классами строк.
+    ///
+    /// Ячейки, занятые объединением сверху, в строке не передаются — они переносятся
+    /// по `rowspan`, как в HTML-таблице. Объединения урезаются до пределов HTML
+    /// (`colspan` — 1000, `rowspan` — 65534), чтобы чужой файл не раздул сетку.
+    pub(crate) fn from_rows<I>(source_rows: I) -> Self
+    where
+        I: IntoIterator<Item = (Option<String>, Vec<SpanCell>)>,
+    {
+        let mut rows = Vec::new();
+        // Сколько ещё строк столбец занят ячейкой с `rowspan` сверху и её текст.
+        let mut carried: Vec<(usize, String)> = Vec::new();
+
+        // Для строк из одной собственной ячейки без перенесённых — её `colspan`.
+        let mut single_spans = Vec::new();
+
+        for (class, source_cells) in source_rows {
+            let mut cells = Vec::new();
+            let sources = source_cells.len();
+            let mut colspan = 0;
+            for cell in source_cells {
+                take_carried(&mut carried, &mut cells);
+                let rowspan = cell.rowspan.clamp(1, MAX_ROWSPAN);
+                colspan = cell.colspan.clamp(1, MAX_COLSPAN);
+                for _ in 0..colspan {
+                    let col = cells.len();
+                    if carried.len() <= col {
+                        carried.resize(col + 1, (0, String::new()));
+                    }
+                    carried[col] = (rowspan - 1, cell.text.clone());
+                    cells.push(cell.text.clone());
+                }
+            }
+            take_carried(&mut carried, &mut cells);
+
+            single_spans.push((sources == 1 && cells.len() == colspan).then_some(colspan));
+            rows.push(GridRow {
+                cells,
+                class,
+                full_width: false,
+            });
+        }
+
+        let width = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+        for (row, single_span) in rows.iter_mut().zip(single_spans) {
+            row.full_width = single_span.is_some_and(|span| span == 1 || span >= width);
+        }
+
+        let header_rows = rows
+            .iter()
+            .take_while(|row| row.has_class("table-header"))
+            .count()
+            .max(1)
+            .min(rows.len());
+        let mut body_start = header_rows;
+        if rows.get(body_start).is_some_and(|row| row.has_class("rn")) {
+            body_start += 1;
+        }
+
+        Self {
+            rows,
+            header_rows,
+            body_start,
+        }
+    }
+
+    /// Все строки таблицы, включая заголовок.
+    #[must_use]
+    pub fn rows(&self) -> &[GridRow] {
+        &self.rows
+    }
+
+    /// Строки заголовка.
+    #[must_use]
+    pub fn header_rows(&self) -> &[GridRow] {
+        &self.rows[..self.header_rows]
+    }
+
+    /// Индекс первой строки данных.
+    #[must_use]
+    pub const fn body_start(&self) -> usize {
+        self.body_start
+    }
+
+    /// Строки данных вместе с их индексом в таблице (0-based, как в диагностике).
+    pub fn body_rows(&self) -> impl Iterator<Item = (usize, &GridRow)> {
+        self.rows.iter().enumerate().skip(self.body_start)
+    }
+
+    /// Число столбцов: длина самой длинной строки.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.cells.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Заголовки столбцов сверху вниз; текст объединённой по вертикали ячейки не повторяется.
+    #[must_use]
+    pub fn header_paths(&self) -> Vec<Vec<String>> {
+        let width = self
+            .header_rows()
+            .iter()
+            .map(|row| row.cells.len())
+            .max()
+            .unwrap_or(0);
+        (0..width)
+            .map(|col| {
+                let mut path: Vec<String> = Vec::new();
+                for row in self.header_rows() {
+                    if let Some(text) = row.cells.get(col)
+                        && path.last() != Some(text)
+                    {
+                        path.push(text.clone());
+                    }
+                }
+                path
+            })
+            .collect()
+    }
+}
+
+/// Таблица документа вместе с заголовком раздела, к которому она относится.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportTable {
+    heading: Option<String>,
+    grid: TableGrid,
+}
+
+impl ReportTable {
+    pub(crate) const fn new(heading: Option<String>, grid: TableGrid) -> Self {
+        Self { heading, grid }
+    }
+
+    /// Подпись таблицы (`caption`) или текст абзаца/заголовка непосредственно перед ней.
+    #[must_use]
+    pub fn heading(&self) -> Option<&str> {
+        self.heading.as_deref()
+    }
+
+    /// Сетка таблицы с развёрнутыми объединёнными ячейками.
+    #[must_use]
+    pub const fn grid(&self) -> &TableGrid {
+        &self.grid
+    }
+
+    /// Забирает сетку таблицы.
+    #[must_use]
+    pub fn into_grid(self) -> TableGrid {
+        self.grid
+    }
+
+    /// Строки заголовка таблицы.
+    #[must_use]
+    pub fn header_rows(&self) -> &[GridRow] {
+        self.grid.header_rows()
+    }
+
+    /// Строки данных вместе с их индексом в таблице.
+    pub fn body_rows(&self) -> impl Iterator<Item = (usize, &GridRow)> {
+        self.grid.body_rows()
+    }
+
+    /// Проверяет, что каждая фраза встречается (как подстрока) в какой-либо ячейке заголовка.
+    #[must_use]
+    pub fn has_headers(&self, phrases: &[&str]) -> bool {
+        phrases.iter().all(|phrase| {
+            self.header_rows()
+                .iter()
+                .flat_map(GridRow::cells)
+                .any(|cell| cell.contains(phrase))
+        })
+    }
+}
+
+/// Дописывает в строку ячейки, занятые `rowspan` из строк выше.
+fn take_carried(carried: &mut [(usize, String)], cells: &mut Vec<String>) {
+    while let Some((rows_left, text)) = carried.get_mut(cells.len()) {
+        if *rows_left == 0 {
+            break;
+        }
+        *rows_left -= 1;
+        cells.push(text.clone());
+    }
+}
+
+fn span(cell: ElementRef<'_>, attr: &str) -> usize {
+    cell.value()
+        .attr(attr)
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(1)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Html;
+
+    fn grid(html: &str) -> TableGrid {
+        let doc = Html::parse_document(html);
+        let table = doc
+            .select(&Selector::parse("table").expect("valid selector"))
+            .next()
+            .expect("table");
+        TableGrid::from_element(table)
+    }
+
+    #[test]
+    fn expands_rowspan_and_colspan() {
+        let grid = grid(
+            r#"<table>
+                <tr class="table-header"><td rowspan="2">Площадка</td><td colspan="2">Начало</td></tr>
+                <tr class="table-header"><td>ЦБ</td><td>ДС</td></tr>
+                <tr class="rn"><td>1</td><td>2</td><td>3</td></tr>
+                <tr><td colspan="3">ИИС3</td></tr>
+                <tr><td rowspan="2">2025</td><td>a</td><td>b</td></tr>
+                <tr><td>c</td><td>d</td></tr>
+            </table>"#,
+        );
+
+        assert_eq!(grid.header_rows().len(), 2);
+        assert_eq!(grid.body_start(), 3);
+        assert_eq!(grid.width(), 3);
+        assert_eq!(
+            grid.header_paths(),
+            [
+                vec!["Площадка".to_string()],
+                vec!["Начало".to_string(), "ЦБ".to_string()],
+                vec!["Начало".to_string(), "ДС".to_string()],
+            ]
+        );
+
+        let body: Vec<_> = grid.body_rows().collect();
+        assert_eq!(body[0].0, 3);
+        assert!(body[0].1.is_full_width());
+        assert_eq!(body[2].1.cells(), ["2025", "c", "d"]);
+        assert!(!body[2].1.is_full_width());
+    }
+
+    #[test]
+    fn detects_full_width_rows_by_own_colspan() {
+        let grid = grid(
+            r#"<table>
+                <tr class="table-header"><td>Год</td><td>Дата</td><td>Сумма</td></tr>
+                <tr><td>ИИС3</td></tr>
+                <tr><td colspan="2">Неполная</td></tr>
+                <tr><td rowspan="2">2025</td><td rowspan="2">01.02.2025</td><td>1</td></tr>
+                <tr><td>2</td></tr>
+            </table>"#,
+        );
+
+        let body: Vec<_> = grid.body_rows().map(|(_, row)| row).collect();
+        // Одиночная ячейка без `colspan` — подзаголовок.
+        assert!(body[0].is_full_width());
+        // `colspan` не покрывает ширину таблицы.
+        assert!(!body[1].is_full_width());
+        assert!(!body[2].is_full_width());
+        // Одна собственная ячейка и две перенесённые `rowspan` — строка данных.
+        assert_eq!(body[3].cells(), ["2025", "01.02.2025", "2"]);
+        assert!(!body[3].is_full_width());
+    }
+
+    #[test]
+    fn clamps_oversized_spans() {
+        let grid = grid(
+            r#"<table>
+                <tr class="table-header"><td colspan="4000000000">Шапка</td></tr>
+                <tr><td rowspan="4000000000">a</td><td>b</td></tr>
+                <tr><td>c</td></tr>
+            </table>"#,
+        );
+
+        assert_eq!(grid.rows()[0].cells().len(), MAX_COLSPAN);
+        assert_eq!(grid.rows()[2].cells(), ["a", "c"]);
+    }
+}
@@ -1,19 +1,28 @@
 #![warn(missing_docs)]
 //! Библиотека для парсинга HTML-отчётов брокера Сбербанка и их агрегации.
 
+mod columns;
 mod diagnostics;
+mod encoding;
 mod error;
+mod grid;
+mod loader;
 mod parse_config;
 mod parser;
 pub mod prelude;
 mod raw;
 mod report;
 mod report_set;
+mod sources;
 mod types;
 mod utils;
+mod xlsx;
 
 pub use crate::diagnostics::ParseWarning;
+pub use crate::encoding::{EncodingSource, TextEncoding};
 pub use crate::error::ReportError;
+pub use crate::grid::{GridRow, ReportTable, TableGrid};
+pub use crate::loader::{LoadOptions, LoadOutcome};
 pub use crate::parse_config::{ParseConfig, ParseMode, ReportSection, SectionSet};
 pub use crate::raw::{DomReport, RawReport};
 pub use crate::report::{Report, ReportBuilder};
@@ -0,0 +1,203 @@
+//! Настройки загрузки каталога с отчётами и результат загрузки с ошибками по файлам.
+
+use crate::error::ReportError;
+use crate::report::Report;
+use crate::report_set::ReportSet;
+use crate::sources::SourceKind;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Файл-источник, найденный при обходе каталога, или ошибка обхода для пути.
+pub type SourceFile = (PathBuf, Result<SourceKind, ReportError>);
+
+/// Настройки обхода каталога при загрузке отчётов.
+///
+/// Шаблоны `include`/`exclude` сопоставляются с путём файла относительно загружаемого
+/// каталога; `*` не выходит за пределы одного каталога, `**` совпадает с любым числом
+/// вложенных каталогов (например, `*/2024/*.html` или `**/*.zip`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// Обходить вложенные каталоги.
+    pub recursive: bool,
+    /// Переходить по символическим ссылкам на файлы и каталоги.
+    pub follow_symlinks: bool,
+    /// Загружать только файлы, подходящие под один из шаблонов; пустой список — все файлы.
+    pub include: Vec<String>,
+    /// Пропускать файлы и каталоги, подходящие под один из шаблонов.
+    pub exclude: Vec<String>,
+    /// Не прерывать загрузку на ошибке, а собирать ошибки по файлам.
+    pub collect_errors: bool,
+}
+
+impl LoadOptions {
+    /// Настройки по умолчанию: только верхний уровень каталога, без ссылок и фильтров.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Включает обход вложенных каталогов.
+    #[must_use]
+    pub const fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Включает переход по символическим ссылкам.
+    #[must_use]
+    pub const fn with_follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Добавляет шаблон включаемых файлов.
+    #[must_use]
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Добавляет шаблон исключаемых файлов и каталогов.
+    #[must_use]
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Включает сбор ошибок по файлам вместо остановки на первой ошибке.
+    #[must_use]
+    pub const fn with_collect_errors(mut self, collect: bool) -> Self {
+        self.collect_errors = collect;
+        self
+    }
+
+    /// Обходит каталог и возвращает подходящие файлы-источники в порядке путей.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если шаблон включения или исключения некорректен. Ошибки обхода
+    /// отдельных путей возвращаются в списке.
+    pub(crate) fn source_files(&self, dir: &Path) -> Result<Vec<SourceFile>, ReportError> {
+        let filter = self.filter()?;
+        let relative = |path: &Path| path.strip_prefix(dir).unwrap_or(path).to_path_buf();
+
+        let mut walker = WalkDir::new(dir)
+            .min_depth(1)
+            .follow_links(self.follow_symlinks)
+            // Записи сортируются по имени, чтобы порядок отчётов был детерминированным.
+            .sort_by_file_name();
+        if !self.recursive {
+            walker = walker.max_depth(1);
+        }
+
+        let mut files = Vec::new();
+        let entries = walker.into_iter().filter_entry(|entry| {
+            !entry.file_type().is_dir() || !filter.is_excluded(&relative(entry.path()))
+        });
+        for entry in entries {
+            match entry {
+                Ok(entry) => {
+                    let path = entry.path();
+                    if !entry.file_type().is_file() || !filter.includes(&relative(path)) {
+                        continue;
+                    }
+                    if let Some(kind) = SourceKind::from_path(path) {
+                        files.push((path.to_path_buf(), Ok(kind)));
+                    }
+                }
+                Err(err) => files.push((
+                    err.path().unwrap_or(dir).to_path_buf(),
+                    Err(io::Error::from(err).into()),
+                )),
+            }
+        }
+        Ok(files)
+    }
+
+    fn filter(&self) -> Result<PathFilter, ReportError> {
+        Ok(PathFilter {
+            include: (!self.include.is_empty())
+                .then(|| build_glob_set(&self.include))
+                .transpose()?,
+            exclude: build_glob_set(&self.exclude)?,
+        })
+    }
+}
+
+/// Скомпилированные шаблоны [`LoadOptions`].
+#[derive(Debug)]
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    /// Проверяет, исключён ли файл или каталог (путь относительно корня обхода).
+    fn is_excluded(&self, relative: &Path) -> bool {
+        self.exclude.is_match(relative)
+    }
+
+    /// Проверяет, нужно ли загружать файл (путь относительно корня обхода).
+    fn includes(&self, relative: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.is_match(relative))
+            && !self.is_excluded(relative)
+    }
+}
+
+/// Результат загрузки каталога: разобранные отчёты и ошибки по отдельным файлам.
+#[derive(Debug, Default)]
+pub struct LoadOutcome {
+    /// Успешно разобранные отчёты.
+    pub reports: ReportSet,
+    /// Файлы, которые не удалось прочитать или разобрать, с причиной; для записей архивов
+    /// и писем путь записи дописывается к пути файла.
+    pub errors: Vec<(PathBuf, ReportError)>,
+}
+
+impl LoadOutcome {
+    /// Возвращает `true`, если все файлы загружены без ошибок.
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Собирает результат из разобранных файлов в порядке обхода.
+    ///
+    /// Без сбора ошибок возвращает первую ошибку и дальше итератор не читает.
+    pub(crate) fn collect<I>(results: I, collect_errors: bool) -> Result<Self, ReportError>
+    where
+        I: IntoIterator<Item = (PathBuf, Result<Vec<Report>, ReportError>)>,
+    {
+        let mut reports = Vec::new();
+        let mut errors = Vec::new();
+        for (path, result) in results {
+            match result {
+                Ok(parsed) => reports.extend(parsed),
+                Err(err) if collect_errors => errors.push((path, err)),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Self {
+            reports: ReportSet::new(reports),
+            errors,
+        })
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, ReportError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| ReportError::Pattern(err.to_string()))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|err| ReportError::Pattern(err.to_string()))
+}
@@ -41,6 +41,56 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let total: sber_invest_report::Money = cash.iter_rows().map(|r| r.amount).sum();
         println!("Движение ДС: {} строк, сумма {}", cash.rows().len(), total);
     }
+    if let Some(operations) = report.cash_operations() {
+        println!("Операции с ДС: {} записей", operations.rows().len());
+    }
+    if let Some(trades) = report.trades() {
+        println!("Сделки с ЦБ: {} записей", trades.rows().len());
+    }
+    if let Some(fx_trades) = report.fx_trades() {
+        println!("Валютные сделки: {} записей", fx_trades.rows().len());
+    }
+    if let Some(movements) = report.security_movements() {
+        println!("Движение ЦБ: {} записей", movements.rows().len());
+    }
+    if let Some(income) = report.income_events() {
+        println!("Выплаты доходов: {} записей", income.rows().len());
+    }
+    if let Some(repo) = report.repo_deals() {
+        println!("Сделки РЕПО: {} записей", repo.rows().len());
+    }
+    if let Some(derivatives) = report.derivatives() {
+        println!(
+            "Срочный рынок: {} позиций, {} строк вариационной маржи",
+            derivatives.positions().len(),
+            derivatives.variation_margin().len()
+        );
+    }
+    if report.uses_leverage() {
+        println!(
+            "Необеспеченные позиции: {} записей",
+            report.margin_rows().count()
+        );
+    }
+    if let Some(otc) = report.otc_operations() {
+        println!("Внебиржевые операции: {} записей", otc.rows().len());
+    }
+    if let Some(blocked) = report.blocked_holdings() {
+        println!("Заблокированные бумаги: {} записей", blocked.rows().len());
+    }
+    for (currency, amount) in report.projected_cash_by_currency() {
+        println!("Прогноз остатка {currency}: {amount}");
+    }
+    if let Some(tax) = report.tax_summary() {
+        let totals = tax.totals();
+        println!(
+            "НДФЛ: база {}, удержано {}, к удержанию {}",
+            totals.tax_base, totals.withheld, totals.due
+        );
+    }
+    if let Some(reference) = report.security_reference() {
+        println!("Справочник ЦБ: {} записей", reference.len());
+    }
     if let Some(iis) = report.iis_contributions() {
         println!("Взносы на ИИС: {} записей", iis.rows().len());
     }
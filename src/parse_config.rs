@@ -12,23 +12,62 @@ pub enum ReportSection {
     Portfolio = 2,
     /// Таблица пополнений ИИС.
     IisContributions = 3,
+    /// Сделки купли/продажи ценных бумаг.
+    Trades = 4,
+    /// Движение денежных средств с отдельными операциями.
+    CashOperations = 5,
+    /// Движение ценных бумаг.
+    SecurityMovements = 6,
+    /// Сделки с валютными инструментами.
+    FxTrades = 7,
+    /// Справочник ценных бумаг.
+    SecurityReference = 8,
+    /// Выплаты дивидендов, купонов и погашений.
+    IncomeEvents = 9,
+    /// Налоговый блок (НДФЛ).
+    TaxSummary = 10,
+    /// Сделки РЕПО.
+    RepoDeals = 11,
+    /// Срочный рынок: позиции и вариационная маржа.
+    Derivatives = 12,
+    /// Незавершённые расчёты и обязательства.
+    PendingSettlements = 13,
+    /// Необеспеченные позиции (маржинальное кредитование).
+    Margin = 14,
+    /// Заблокированные и обременённые ценные бумаги.
+    BlockedHoldings = 15,
+    /// Внебиржевые сделки и переводы ЦБ между брокерами.
+    OtcOperations = 16,
 }
 
 impl ReportSection {
-    const fn bit(self) -> u8 {
-        1 << self as u8
+    const fn bit(self) -> u32 {
+        1 << self as u32
     }
 }
 
 /// Набор секций, включаемых в парсинг.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct SectionSet(u8);
+pub struct SectionSet(u32);
 
 impl SectionSet {
-    const ALL_BITS: u8 = ReportSection::AssetValuation.bit()
+    const ALL_BITS: u32 = ReportSection::AssetValuation.bit()
         | ReportSection::CashFlowSummary.bit()
         | ReportSection::Portfolio.bit()
-        | ReportSection::IisContributions.bit();
+        | ReportSection::IisContributions.bit()
+        | ReportSection::Trades.bit()
+        | ReportSection::CashOperations.bit()
+        | ReportSection::SecurityMovements.bit()
+        | ReportSection::FxTrades.bit()
+        | ReportSection::SecurityReference.bit()
+        | ReportSection::IncomeEvents.bit()
+        | ReportSection::TaxSummary.bit()
+        | ReportSection::RepoDeals.bit()
+        | ReportSection::Derivatives.bit()
+        | ReportSection::PendingSettlements.bit()
+        | ReportSection::Margin.bit()
+        | ReportSection::BlockedHoldings.bit()
+        | ReportSection::OtcOperations.bit();
 
     /// Включает все известные секции отчёта.
     #[must_use]
@@ -2,25 +2,32 @@
 
 use std::sync::LazyLock;
 
+use crate::columns::{BodyRow, ColumnMap, ColumnSpec};
 use crate::diagnostics::ParseWarning;
 use crate::error::ReportError;
+use crate::grid::{GridRow, TableGrid};
 use crate::parse_config::ParseMode;
 use crate::raw::DomReport;
 use crate::types::{
-    AccountId, AccountKind, AssetValuation, AssetValuationRow, CashFlowKind, CashFlowRow,
-    CashFlowSummary, IisContribution, IisContributionsTable, IisLimit, Portfolio, PortfolioMarket,
-    ReportMetadata, SecurityPosition,
+    AccountId, AccountKind, AcquisitionCost, AssetValuation, AssetValuationRow, BlockReason,
+    BlockedHolding, BlockedHoldingsTable, CashFlowKind, CashFlowRow, CashFlowSummary,
+    CashOperation, CashOperationsTable, CurrencyPair, DerivativePosition, DerivativesSection,
+    FxTrade, FxTradesTable, IisContribution, IisContributionsTable, IisLimit, IncomeEvent,
+    IncomeEventsTable, IncomeKind, MarginExposureKind, MarginPosition, MarginSection, OtcDirection,
+    OtcOperation, OtcOperationsTable, PendingSettlement, PendingSettlements, Portfolio,
+    PortfolioMarket, PortfolioMarketKind, RepoDeal, RepoDealsTable, RepoDirection, RepoLeg,
+    ReportMetadata, SecurityInfo, SecurityKind, SecurityMovement, SecurityMovementKind,
+    SecurityMovementsTable, SecurityPosition, SecurityReference, SettlementSource, TaxRow,
+    TaxSummary, Trade, TradeSide, TradesTable, VariationMarginRow,
 };
 use crate::utils::{
-    capitalize_words, capture_text, collect_text, find_table_with_headers, parse_date,
-    parse_money_or_zero,
+    capitalize_words, capture_text, parse_date, parse_money_or_zero, parse_optional_date,
 };
 use regex::Regex;
 use rust_decimal::Decimal;
-use scraper::{ElementRef, Selector};
 
 // Жёстко под шапку отчёта: три даты в одном заголовке.
-static PERIOD_RE: LazyLock<Regex> = LazyLock::new(|| {
+pub(crate) static PERIOD_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
         r"за период с\s+(\d{2}\.\d{2}\.\d{4})\s+по\s+(\d{2}\.\d{2}\.\d{4}),\s*дата создания\s+(\d{2}\.\d{2}\.\d{4})",
     )
@@ -34,31 +41,477 @@ static CONTRACT_RE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"Договор[^A-Za-z0-9]*([A-Za-z0-9]+)").expect("valid contract regex")
 });
 
-static RATING_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("table.RatingAssets").expect("valid rating selector"));
-static TR_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("tr").expect("valid tr selector"));
-static TD_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("td").expect("valid td selector"));
-static H3_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("h3").expect("valid h3 selector"));
-static P_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("p").expect("valid p selector"));
+// Строка с датой исполнения в таблице требований и обязательств валютного рынка.
+const SETTLEMENT_DATE_PREFIX: &str = "Дата исполнения:";
 
 const TABLE_ASSET_VALUATION: &str = "RatingAssets";
 const TABLE_CASH_FLOW: &str = "CashFlowSummary";
 const TABLE_PORTFOLIO: &str = "Portfolio";
 const TABLE_IIS: &str = "IISContributions";
+const TABLE_TRADES: &str = "Trades";
+const TABLE_CASH_OPERATIONS: &str = "CashOperations";
+const TABLE_SECURITY_MOVEMENTS: &str = "SecurityMovements";
+const TABLE_FX_TRADES: &str = "FxTrades";
+const TABLE_SECURITY_REFERENCE: &str = "SecurityReference";
+const TABLE_INCOME_EVENTS: &str = "IncomeEvents";
+const TABLE_TAX_SUMMARY: &str = "TaxSummary";
+const TABLE_REPO_DEALS: &str = "RepoDeals";
+const TABLE_DERIVATIVES: &str = "Derivatives";
+const TABLE_PENDING_SETTLEMENTS: &str = "PendingSettlements";
+const TABLE_MARGIN: &str = "Margin";
+const TABLE_BLOCKED_HOLDINGS: &str = "BlockedHoldings";
+const TABLE_OTC_OPERATIONS: &str = "OtcOperations";
 
-const CASH_FLOW_RULES: [(&str, CashFlowKind); 6] = [
+// Фразы заголовков, по которым находятся таблицы отчёта.
+pub(crate) const ASSET_VALUATION_HEADERS: [&str; 4] = [
+    "Торговая площадка",
+    "Начало периода",
+    "Конец периода",
+    "Изменение за период",
+];
+const CASH_FLOW_HEADERS: [&str; 3] = ["Описание", "Сумма", "Валюта"];
+const CASH_OPERATIONS_HEADERS: [&str; 4] = [
+    "Дата",
+    "Описание операции",
+    "Сумма зачисления",
+    "Сумма списания",
+];
+const PORTFOLIO_HEADERS: [&str; 4] = [
+    "ISIN",
+    "Рыночная стоимость, без НКД",
+    "Рыночная цена",
+    "Плановые зачисления",
+];
+const REGISTRAR_HEADERS: [&str; 2] = ["Расчетная стоимость пая", "Дата расчета стоимости пая"];
+const IIS_HEADERS: [&str; 6] = [
+    "Год",
+    "Лимит, руб.",
+    "Дата операции",
+    "Сумма, руб.",
+    "Основание операции",
+    "Остаток лимита",
+];
+const TRADES_HEADERS: [&str; 4] = ["Дата заключения", "Дата расчетов", "Код ЦБ", "Номер сделки"];
+const SECURITY_MOVEMENTS_HEADERS: [&str; 5] =
+    ["Дата", "ISIN", "Вид операции", "Зачислено", "Списано"];
+const FX_TRADES_HEADERS: [&str; 3] = ["Валютный инструмент", "Дата заключения", "Номер сделки"];
+const INCOME_EVENTS_HEADERS: [&str; 3] = ["Дата выплаты", "Вид выплаты", "Удержанный налог"];
+const TAX_SUMMARY_HEADERS: [&str; 3] = ["Код дохода", "Ставка", "Налоговая база"];
+const REPO_DEALS_HEADERS: [&str; 3] = ["Ставка РЕПО", "Сумма 1 части", "Сумма 2 части"];
+const DERIVATIVE_POSITIONS_HEADERS: [&str; 2] = ["Код контракта", "Гарантийное обеспечение"];
+const VARIATION_MARGIN_HEADERS: [&str; 2] = ["Код контракта", "Вариационная маржа"];
+const PENDING_CASH_HEADERS: [&str; 2] = [
+    "Плановые зачисления по операциям",
+    "Плановые списания по операциям",
+];
+const PENDING_FX_HEADERS: [&str; 3] = ["Валюта", "Требования", "Обязательства"];
+const MARGIN_HEADERS: [&str; 2] = ["Тип задолженности", "Оценка задолженности"];
+const BLOCKED_HOLDINGS_HEADERS: [&str; 2] = ["ISIN", "Причина блокировки"];
+const OTC_OPERATIONS_HEADERS: [&str; 2] = ["Стоимость приобретения", "Дата приобретения"];
+const SECURITY_REFERENCE_HEADERS: [&str; 3] = ["Код", "ISIN ценной бумаги", "Эмитент"];
+
+// Все таблицы, которые разбирает крейт: остальные попадают в инвентаризацию как нераспознанные.
+const KNOWN_TABLE_HEADERS: [&[&str]; 20] = [
+    &ASSET_VALUATION_HEADERS,
+    &CASH_FLOW_HEADERS,
+    &CASH_OPERATIONS_HEADERS,
+    &PORTFOLIO_HEADERS,
+    &REGISTRAR_HEADERS,
+    &IIS_HEADERS,
+    &TRADES_HEADERS,
+    &SECURITY_MOVEMENTS_HEADERS,
+    &FX_TRADES_HEADERS,
+    &INCOME_EVENTS_HEADERS,
+    &TAX_SUMMARY_HEADERS,
+    &REPO_DEALS_HEADERS,
+    &DERIVATIVE_POSITIONS_HEADERS,
+    &VARIATION_MARGIN_HEADERS,
+    &PENDING_CASH_HEADERS,
+    &PENDING_FX_HEADERS,
+    &MARGIN_HEADERS,
+    &BLOCKED_HOLDINGS_HEADERS,
+    &OTC_OPERATIONS_HEADERS,
+    &SECURITY_REFERENCE_HEADERS,
+];
+
+// Логические имена столбцов совпадают с именами в ошибках разбора чисел.
+const ASSET_VALUATION_COLUMNS: [ColumnSpec; 10] = [
+    ColumnSpec::new("Торговая площадка"),
+    ColumnSpec::grouped("ЦБ начало", "Начало периода", "Ценные бумаги"),
+    ColumnSpec::grouped(
+        "Денежные средства начало",
+        "Начало периода",
+        "Денежные средства",
+    ),
+    ColumnSpec::grouped("Всего начало", "Начало периода", "Всего"),
+    ColumnSpec::grouped("ЦБ конец", "Конец периода", "Ценные бумаги"),
+    ColumnSpec::grouped(
+        "Денежные средства конец",
+        "Конец периода",
+        "Денежные средства",
+    ),
+    ColumnSpec::grouped("Всего конец", "Конец периода", "Всего"),
+    ColumnSpec::grouped("ЦБ изменение", "Изменение за период", "Ценные бумаги"),
+    ColumnSpec::grouped(
+        "Денежные средства изменение",
+        "Изменение за период",
+        "Денежные средства",
+    ),
+    ColumnSpec::grouped("Всего изменение", "Изменение за период", "Всего"),
+];
+
+const CASH_FLOW_COLUMNS: [ColumnSpec; 3] = [
+    ColumnSpec::new("Описание"),
+    ColumnSpec::named("Сумма ДС", "Сумма"),
+    ColumnSpec::new("Валюта"),
+];
+
+// Колонка остатка есть не во всех вариантах отчёта.
+const CASH_OPERATIONS_COLUMNS: [ColumnSpec; 7] = [
+    ColumnSpec::new("Дата"),
+    ColumnSpec::new("Торговая площадка"),
+    ColumnSpec::new("Описание операции"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Сумма зачисления"),
+    ColumnSpec::new("Сумма списания"),
+    ColumnSpec::new("Остаток").optional(),
+];
+
+// В одноуровневом заголовке группы не видны: одинаковые столбцы различаются порядком.
+const PORTFOLIO_COLUMNS: [ColumnSpec; 18] = [
+    ColumnSpec::new("Наименование"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::grouped("Количество начало", "Начало периода", "Количество"),
+    ColumnSpec::grouped("Номинал начало", "Начало периода", "Номинал"),
+    ColumnSpec::grouped("Цена начало", "Начало периода", "Рыночная цена"),
+    ColumnSpec::grouped(
+        "Стоимость без НКД начало",
+        "Начало периода",
+        "Рыночная стоимость, без НКД",
+    ),
+    ColumnSpec::grouped("НКД начало", "Начало периода", "НКД"),
+    ColumnSpec::grouped("Количество конец", "Конец периода", "Количество"),
+    ColumnSpec::grouped("Номинал конец", "Конец периода", "Номинал"),
+    ColumnSpec::grouped("Цена конец", "Конец периода", "Рыночная цена"),
+    ColumnSpec::grouped(
+        "Стоимость без НКД конец",
+        "Конец периода",
+        "Рыночная стоимость, без НКД",
+    ),
+    ColumnSpec::grouped("НКД конец", "Конец периода", "НКД"),
+    ColumnSpec::grouped("Количество изменение", "Изменение за период", "Количество"),
+    ColumnSpec::grouped(
+        "Стоимость изменение",
+        "Изменение за период",
+        "Рыночная стоимость",
+    ),
+    ColumnSpec::grouped(
+        "Плановые зачисления",
+        "Плановые показатели",
+        "Плановые зачисления",
+    ),
+    ColumnSpec::grouped(
+        "Плановые списания",
+        "Плановые показатели",
+        "Плановые списания",
+    ),
+    ColumnSpec::grouped(
+        "Плановый исходящий остаток",
+        "Плановые показатели",
+        "Плановый исходящий остаток",
+    ),
+];
+
+const REGISTRAR_COLUMNS: [ColumnSpec; 8] = [
+    ColumnSpec::new("Наименование"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::named("Количество паёв начало", "Количество паёв на начало"),
+    ColumnSpec::named("Количество паёв конец", "Количество паёв на конец"),
+    ColumnSpec::new("Расчетная стоимость пая"),
+    ColumnSpec::new("Дата расчета стоимости пая"),
+    ColumnSpec::new("Стоимость на конец"),
+];
+
+const IIS_COLUMNS: [ColumnSpec; 6] = [
+    ColumnSpec::new("Год"),
+    ColumnSpec::named("Лимит ИИС", "Лимит"),
+    ColumnSpec::new("Дата операции"),
+    ColumnSpec::named("Сумма ИИС", "Сумма"),
+    ColumnSpec::new("Основание операции"),
+    ColumnSpec::new("Остаток лимита"),
+];
+
+const TRADES_COLUMNS: [ColumnSpec; 16] = [
+    ColumnSpec::new("Дата заключения"),
+    ColumnSpec::new("Дата расчетов"),
+    ColumnSpec::new("Время заключения"),
+    ColumnSpec::new("Наименование ЦБ"),
+    ColumnSpec::new("Код ЦБ"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Вид"),
+    ColumnSpec::new("Количество"),
+    ColumnSpec::new("Цена"),
+    ColumnSpec::new("Сумма"),
+    ColumnSpec::new("НКД"),
+    ColumnSpec::new("Комиссия Брокера"),
+    ColumnSpec::new("Комиссия Биржи"),
+    ColumnSpec::new("Номер сделки"),
+    ColumnSpec::new("Комментарий"),
+    ColumnSpec::new("Статус сделки"),
+];
+
+const SECURITY_MOVEMENTS_COLUMNS: [ColumnSpec; 8] = [
+    ColumnSpec::new("Дата"),
+    ColumnSpec::new("Торговая площадка"),
+    ColumnSpec::new("Наименование ЦБ"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Вид операции"),
+    ColumnSpec::new("Зачислено"),
+    ColumnSpec::new("Списано"),
+    ColumnSpec::new("Основание операции"),
+];
+
+const FX_TRADES_COLUMNS: [ColumnSpec; 12] = [
+    ColumnSpec::new("Валютный инструмент"),
+    ColumnSpec::new("Дата заключения"),
+    ColumnSpec::new("Дата расчетов"),
+    ColumnSpec::new("Время заключения"),
+    ColumnSpec::new("Вид"),
+    ColumnSpec::new("Количество базовой валюты"),
+    ColumnSpec::new("Цена"),
+    ColumnSpec::new("Сумма сделки"),
+    ColumnSpec::new("Комиссия Брокера"),
+    ColumnSpec::new("Комиссия Биржи"),
+    ColumnSpec::new("Номер сделки"),
+    ColumnSpec::new("Комментарий"),
+];
+
+const INCOME_EVENTS_COLUMNS: [ColumnSpec; 8] = [
+    ColumnSpec::new("Дата выплаты"),
+    ColumnSpec::new("Наименование ЦБ"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Вид выплаты"),
+    ColumnSpec::new("Сумма до налогообложения"),
+    ColumnSpec::new("Удержанный налог"),
+    ColumnSpec::new("Сумма к выплате"),
+    ColumnSpec::new("Валюта"),
+];
+
+const TAX_SUMMARY_COLUMNS: [ColumnSpec; 6] = [
+    ColumnSpec::new("Код дохода"),
+    ColumnSpec::named("Ставка налога", "Ставка"),
+    ColumnSpec::new("Налоговая база"),
+    ColumnSpec::new("Исчисленный налог"),
+    ColumnSpec::new("Удержанный налог"),
+    ColumnSpec::new("Налог к удержанию"),
+];
+
+const REPO_DEALS_COLUMNS: [ColumnSpec; 15] = [
+    ColumnSpec::new("Дата заключения"),
+    ColumnSpec::new("Дата исполнения 1 части"),
+    ColumnSpec::new("Дата исполнения 2 части"),
+    ColumnSpec::new("Наименование ЦБ"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Вид"),
+    ColumnSpec::new("Количество"),
+    ColumnSpec::new("Цена 1 части"),
+    ColumnSpec::new("Сумма 1 части"),
+    ColumnSpec::new("Цена 2 части"),
+    ColumnSpec::new("Сумма 2 части"),
+    ColumnSpec::new("Ставка РЕПО"),
+    ColumnSpec::new("Доход по сделке"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Номер сделки"),
+];
+
+const DERIVATIVE_POSITIONS_COLUMNS: [ColumnSpec; 9] = [
+    ColumnSpec::new("Код контракта"),
+    ColumnSpec::new("Тип контракта"),
+    ColumnSpec::new("Базовый актив"),
+    ColumnSpec::new("Дата исполнения"),
+    ColumnSpec::new("Позиция на начало"),
+    ColumnSpec::new("Позиция на конец"),
+    ColumnSpec::new("Расчетная цена"),
+    ColumnSpec::new("Гарантийное обеспечение"),
+    ColumnSpec::new("Валюта"),
+];
+
+const VARIATION_MARGIN_COLUMNS: [ColumnSpec; 5] = [
+    ColumnSpec::new("Дата"),
+    ColumnSpec::new("Код контракта"),
+    ColumnSpec::new("Вариационная маржа"),
+    ColumnSpec::new("Биржевой сбор"),
+    ColumnSpec::new("Валюта"),
+];
+
+// Остатки таблицы «Денежные средства» нужны только для сверки и не разбираются.
+const PENDING_CASH_COLUMNS: [ColumnSpec; 9] = [
+    ColumnSpec::new("Торговая площадка"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Курс на конец периода").optional(),
+    ColumnSpec::new("Начало периода").optional(),
+    ColumnSpec::new("Изменение за период").optional(),
+    ColumnSpec::new("Конец периода").optional(),
+    ColumnSpec::named("Плановые зачисления", "Плановые зачисления по операциям"),
+    ColumnSpec::named("Плановые списания", "Плановые списания по операциям"),
+    ColumnSpec::new("Плановый исходящий остаток").optional(),
+];
+
+const PENDING_FX_COLUMNS: [ColumnSpec; 3] = [
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Требования"),
+    ColumnSpec::new("Обязательства"),
+];
+
+const MARGIN_COLUMNS: [ColumnSpec; 7] = [
+    ColumnSpec::new("Инструмент"),
+    ColumnSpec::new("Тип задолженности"),
+    ColumnSpec::new("Количество"),
+    ColumnSpec::new("Оценка задолженности"),
+    ColumnSpec::new("Ставка"),
+    ColumnSpec::new("Плата за период"),
+    ColumnSpec::new("Валюта"),
+];
+
+const BLOCKED_HOLDINGS_COLUMNS: [ColumnSpec; 6] = [
+    ColumnSpec::new("Наименование"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Количество"),
+    ColumnSpec::new("Оценка"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Причина блокировки"),
+];
+
+const OTC_OPERATIONS_COLUMNS: [ColumnSpec; 10] = [
+    ColumnSpec::new("Дата"),
+    ColumnSpec::new("Наименование ЦБ"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Вид операции"),
+    ColumnSpec::new("Количество"),
+    ColumnSpec::new("Цена приобретения"),
+    ColumnSpec::new("Стоимость приобретения"),
+    ColumnSpec::new("Дата приобретения"),
+    ColumnSpec::new("Валюта"),
+    ColumnSpec::new("Контрагент"),
+];
+
+// Валюта номинала есть только в расширенном варианте справочника.
+const SECURITY_REFERENCE_COLUMNS: [ColumnSpec; 7] = [
+    ColumnSpec::new("Наименование"),
+    ColumnSpec::new("Код"),
+    ColumnSpec::new("ISIN"),
+    ColumnSpec::new("Эмитент"),
+    ColumnSpec::new("Вид"),
+    ColumnSpec::new("Выпуск"),
+    ColumnSpec::new("Валюта номинала").optional(),
+];
+
+// Плата за необеспеченные позиции проверяется раньше общей комиссии брокера.
+const CASH_FLOW_RULES: [(&str, CashFlowKind); 10] = [
     ("входящий остаток", CashFlowKind::OpeningBalance),
     ("сальдо расчетов по сделкам", CashFlowKind::TradesNet),
     ("корпоративные действия", CashFlowKind::CorporateActions),
+    ("необеспеченн", CashFlowKind::MarginFee),
+    ("маржинальн", CashFlowKind::MarginFee),
     ("комиссия брокера", CashFlowKind::BrokerFee),
     ("комиссия биржи", CashFlowKind::ExchangeFee),
+    ("репо", CashFlowKind::RepoIncome),
+    ("вариационная маржа", CashFlowKind::VariationMargin),
     ("исходящий остаток", CashFlowKind::ClosingBalance),
 ];
 
+// Частичное погашение проверяется раньше полного.
+const INCOME_RULES: [(&str, IncomeKind); 5] = [
+    ("дивиденд", IncomeKind::Dividend),
+    ("купон", IncomeKind::Coupon),
+    ("частичн", IncomeKind::PartialAmortization),
+    ("амортизац", IncomeKind::PartialAmortization),
+    ("погашен", IncomeKind::Redemption),
+];
+
+const BLOCK_REASON_RULES: [(&str, BlockReason); 6] = [
+    ("иностранн", BlockReason::ForeignInfrastructure),
+    ("euroclear", BlockReason::ForeignInfrastructure),
+    ("clearstream", BlockReason::ForeignInfrastructure),
+    ("залог", BlockReason::Pledge),
+    ("обремен", BlockReason::Pledge),
+    ("арест", BlockReason::Arrest),
+];
+
+// Порядок важен: более специфичные операции проверяются раньше общих «зачисление/списание».
+const SECURITY_MOVEMENT_RULES: [(&str, SecurityMovementKind); 14] = [
+    ("конвертац", SecurityMovementKind::Conversion),
+    ("дроблен", SecurityMovementKind::Split),
+    ("консолидац", SecurityMovementKind::Split),
+    ("погашен", SecurityMovementKind::Redemption),
+    ("амортизац", SecurityMovementKind::Redemption),
+    ("сделк", SecurityMovementKind::Trade),
+    ("покупк", SecurityMovementKind::Trade),
+    ("продаж", SecurityMovementKind::Trade),
+    ("перевод", SecurityMovementKind::Transfer),
+    ("другого брокера", SecurityMovementKind::Transfer),
+    ("другого депозитария", SecurityMovementKind::Transfer),
+    ("депозитар", SecurityMovementKind::DepositoryOperation),
+    ("блокиров", SecurityMovementKind::DepositoryOperation),
+    ("разделам", SecurityMovementKind::DepositoryOperation),
+];
+
+// Структурные бумаги проверяются раньше облигаций: «Облигация структурная».
+const SECURITY_TYPE_RULES: [(&str, SecurityKind); 8] = [
+    ("структурн", SecurityKind::StructuredNote),
+    ("депозитарн", SecurityKind::DepositaryReceipt),
+    ("расписк", SecurityKind::DepositaryReceipt),
+    ("облигац", SecurityKind::Bond),
+    ("пай", SecurityKind::FundUnit),
+    ("паи", SecurityKind::FundUnit),
+    ("фонд", SecurityKind::FundUnit),
+    ("акци", SecurityKind::Share),
+];
+
+/// Где в наименовании бумаги должно стоять слово правила.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NamePosition {
+    /// Любое целое слово.
+    Anywhere,
+    /// Последнее слово: тип акции или расписки после наименования эмитента («Сбербанк ао»).
+    Last,
+    /// Слово перед номером выпуска («БО-001Р-02»).
+    BeforeSeries,
+}
+
+// Эвристики по словам в наименовании бумаги. Двухбуквенные сокращения встречаются и внутри
+// обычных названий, поэтому учитываются только на своих местах.
+const SECURITY_NAME_RULES: [(&str, NamePosition, SecurityKind); 14] = [
+    ("нота", NamePosition::Anywhere, SecurityKind::StructuredNote),
+    ("ноты", NamePosition::Anywhere, SecurityKind::StructuredNote),
+    ("офз", NamePosition::Anywhere, SecurityKind::Bond),
+    ("обл", NamePosition::Anywhere, SecurityKind::Bond),
+    ("бо", NamePosition::BeforeSeries, SecurityKind::Bond),
+    ("etf", NamePosition::Anywhere, SecurityKind::FundUnit),
+    ("бпиф", NamePosition::Anywhere, SecurityKind::FundUnit),
+    ("пиф", NamePosition::Anywhere, SecurityKind::FundUnit),
+    (
+        "adr",
+        NamePosition::Anywhere,
+        SecurityKind::DepositaryReceipt,
+    ),
+    (
+        "gdr",
+        NamePosition::Anywhere,
+        SecurityKind::DepositaryReceipt,
+    ),
+    ("др", NamePosition::Last, SecurityKind::DepositaryReceipt),
+    (
+        "гдр",
+        NamePosition::Anywhere,
+        SecurityKind::DepositaryReceipt,
+    ),
+    ("ао", NamePosition::Last, SecurityKind::Share),
+    ("ап", NamePosition::Last, SecurityKind::Share),
+];
+
 impl DomReport {
     /// Извлекает метаданные из шапки отчёта.
     ///
@@ -67,10 +520,7 @@ impl DomReport {
     /// Возвращает ошибку, если отсутствуют обязательные поля шапки или не удалось распарсить даты.
     pub fn meta(&self) -> Result<ReportMetadata, ReportError> {
         let heading_text = self
-            .doc
-            .select(&H3_SELECTOR)
-            .next()
-            .map(collect_text)
+            .heading_text()
             .ok_or(ReportError::MissingField { field: "heading" })?;
 
         let period_caps = PERIOD_RE
@@ -83,12 +533,8 @@ impl DomReport {
 
         // Ищем блок "Инвестор" без стабильного селектора.
         let investor_text = self
-            .doc
-            .select(&P_SELECTOR)
-            .find_map(|p| {
-                let text: String = p.text().collect();
-                text.to_lowercase().contains("инвестор").then_some(text)
-            })
+            .paragraphs()
+            .find(|text| text.to_lowercase().contains("инвестор"))
             .ok_or(ReportError::MissingField { field: "investor" })?;
 
         let investor_name = capture_text(&investor_text, &INVESTOR_RE)
@@ -137,10 +583,8 @@ impl DomReport {
         mode: ParseMode,
         warnings: &mut Vec<ParseWarning>,
     ) -> Result<AssetValuation, ReportError> {
-        let table = self
-            .doc
-            .select(&RATING_SELECTOR)
-            .next()
+        let grid = self
+            .asset_valuation_grid()
             .ok_or(ReportError::TableNotFound {
                 table: TABLE_ASSET_VALUATION,
             })?;
@@ -149,38 +593,37 @@ impl DomReport {
         let mut total_delta = Decimal::ZERO;
         let mut summary_seen = false;
 
-        for (idx, tr) in table.select(&TR_SELECTOR).enumerate() {
-            if idx < 3 {
-                // Первые строки — заголовки, не данные.
-                continue;
-            }
-            let cells = row_cells(tr);
-            if cells.is_empty() {
-                continue;
-            }
-            if cells[0].to_lowercase().contains("итого") {
-                if let Some(last) = cells.last() {
-                    total_delta = parse_money_or_zero(last, "Итого")?;
-                    summary_seen = true;
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_ASSET_VALUATION,
+            &ASSET_VALUATION_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for body_row in columns.body_rows(&grid, mode, warnings, |_| false)? {
+            let row = match body_row {
+                BodyRow::Data(_, row) => row,
+                BodyRow::Total(cells) => {
+                    if let Some(last) = cells.last() {
+                        total_delta = parse_money_or_zero(last, "Итого")?;
+                        summary_seen = true;
+                    }
+                    continue;
                 }
-                continue;
-            }
-            if cells.len() < 10 {
-                ensure_min_cells(TABLE_ASSET_VALUATION, idx, cells.len(), 10, mode, warnings)?;
-                continue;
-            }
+                BodyRow::Marker(_) => continue,
+            };
 
             rows.push(AssetValuationRow {
-                venue: cells[0].clone(),
-                start_securities: parse_money_or_zero(&cells[1], "ЦБ начало")?,
-                start_cash: parse_money_or_zero(&cells[2], "Денежные средства начало")?,
-                start_total: parse_money_or_zero(&cells[3], "Всего начало")?,
-                end_securities: parse_money_or_zero(&cells[4], "ЦБ конец")?,
-                end_cash: parse_money_or_zero(&cells[5], "Денежные средства конец")?,
-                end_total: parse_money_or_zero(&cells[6], "Всего конец")?,
-                delta_securities: parse_money_or_zero(&cells[7], "ЦБ изменение")?,
-                delta_cash: parse_money_or_zero(&cells[8], "Денежные средства изменение")?,
-                delta_total: parse_money_or_zero(&cells[9], "Всего изменение")?,
+                venue: row.string("Торговая площадка")?,
+                start_securities: row.money("ЦБ начало")?,
+                start_cash: row.money("Денежные средства начало")?,
+                start_total: row.money("Всего начало")?,
+                end_securities: row.money("ЦБ конец")?,
+                end_cash: row.money("Денежные средства конец")?,
+                end_total: row.money("Всего конец")?,
+                delta_securities: row.money("ЦБ изменение")?,
+                delta_cash: row.money("Денежные средства изменение")?,
+                delta_total: row.money("Всего изменение")?,
             });
         }
 
@@ -209,36 +652,77 @@ impl DomReport {
         mode: ParseMode,
         warnings: &mut Vec<ParseWarning>,
     ) -> Result<CashFlowSummary, ReportError> {
-        let table = find_table_with_headers(&self.doc, &["Описание", "Сумма", "Валюта"], None)
+        let grid = self
+            .table_grid(&CASH_FLOW_HEADERS)
             .ok_or(ReportError::TableNotFound {
                 table: TABLE_CASH_FLOW,
             })?;
 
         let mut rows = Vec::new();
-        for (idx, tr) in table.select(&TR_SELECTOR).enumerate() {
-            if idx < 2 {
-                continue;
-            }
-            let cells = row_cells(tr);
-            if cells.len() < 3 {
-                ensure_min_cells(TABLE_CASH_FLOW, idx, cells.len(), 3, mode, warnings)?;
-                continue;
-            }
-            if cells.iter().all(String::is_empty) {
-                continue;
-            }
-            let description = cells[0].clone();
+        let columns =
+            ColumnMap::resolve(&grid, TABLE_CASH_FLOW, &CASH_FLOW_COLUMNS, mode, warnings)?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            let description = row.string("Описание")?;
             rows.push(CashFlowRow {
                 kind: classify_cash_flow(&description),
                 description_raw: description,
-                amount: parse_money_or_zero(&cells[1], "Сумма ДС")?,
-                currency: cells[2].clone(),
+                amount: row.money("Сумма ДС")?,
+                currency: row.string("Валюта")?,
             });
         }
 
         Ok(CashFlowSummary::new(rows))
     }
 
+    /// Парсит таблицу «Движение денежных средств за период» с отдельными операциями.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_cash_operations(&self) -> Result<CashOperationsTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_cash_operations_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_cash_operations_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<CashOperationsTable, ReportError> {
+        let grid = self
+            .table_grid(&CASH_OPERATIONS_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_CASH_OPERATIONS,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_CASH_OPERATIONS,
+            &CASH_OPERATIONS_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            let balance = Some(row.text("Остаток")?)
+                .filter(|value| !value.is_empty())
+                .map(|value| parse_money_or_zero(value, "Остаток"))
+                .transpose()?;
+
+            rows.push(CashOperation {
+                date: row.date("Дата")?,
+                venue: row.string("Торговая площадка")?,
+                description: row.string("Описание операции")?,
+                currency: row.string("Валюта")?,
+                credit: row.money("Сумма зачисления")?,
+                debit: row.money("Сумма списания")?,
+                balance,
+            });
+        }
+
+        Ok(CashOperationsTable::new(rows))
+    }
+
     /// Парсит таблицу «Портфель ценных бумаг».
     ///
     /// # Errors
@@ -254,86 +738,133 @@ impl DomReport {
         mode: ParseMode,
         warnings: &mut Vec<ParseWarning>,
     ) -> Result<Portfolio, ReportError> {
-        // Здесь заголовок занимает две строки.
-        let table = find_table_with_headers(
-            &self.doc,
-            &[
-                "ISIN",
-                "Рыночная стоимость, без НКД",
-                "Рыночная цена",
-                "Плановые зачисления",
-            ],
-            Some(2),
-        )
-        .ok_or(ReportError::TableNotFound {
-            table: TABLE_PORTFOLIO,
-        })?;
+        let table = self.table_grid(&PORTFOLIO_HEADERS);
+        let registrar = self.parse_registrar_holdings(mode, warnings)?;
+        if table.is_none() && registrar.is_none() {
+            return Err(ReportError::TableNotFound {
+                table: TABLE_PORTFOLIO,
+            });
+        }
 
         let mut markets: Vec<PortfolioMarket> = Vec::new();
         let mut current_market: Option<PortfolioMarket> = None;
 
-        for (idx, tr) in table.select(&TR_SELECTOR).enumerate() {
-            if idx < 3 {
-                continue;
-            }
-            let cells = row_cells(tr);
-            if cells.is_empty() {
-                continue;
-            }
-            if cells[0].starts_with("Площадка") {
-                // Разделитель блоков по рынкам.
-                if let Some(m) = current_market.take() {
-                    markets.push(m);
-                }
-                let name = cells[0].trim_start_matches("Площадка:").trim().to_string();
-                current_market = Some(PortfolioMarket {
-                    name,
-                    positions: Vec::new(),
-                });
-                continue;
-            }
-            if cells.len() < 18 {
-                ensure_min_cells(TABLE_PORTFOLIO, idx, cells.len(), 18, mode, warnings)?;
-                continue;
-            }
-            let position = SecurityPosition {
-                name: cells[0].clone(),
-                isin: cells[1].clone(),
-                price_currency: cells[2].clone(),
-                qty_start: parse_money_or_zero(&cells[3], "Количество начало")?,
-                nominal_start: parse_money_or_zero(&cells[4], "Номинал начало")?,
-                price_start: parse_money_or_zero(&cells[5], "Цена начало")?,
-                value_start_no_ai: parse_money_or_zero(&cells[6], "Стоимость без НКД начало")?,
-                accrued_interest_start: parse_money_or_zero(&cells[7], "НКД начало")?,
-                qty_end: parse_money_or_zero(&cells[8], "Количество конец")?,
-                nominal_end: parse_money_or_zero(&cells[9], "Номинал конец")?,
-                price_end: parse_money_or_zero(&cells[10], "Цена конец")?,
-                value_end_no_ai: parse_money_or_zero(&cells[11], "Стоимость без НКД конец")?,
-                accrued_interest_end: parse_money_or_zero(&cells[12], "НКД конец")?,
-                qty_delta: parse_money_or_zero(&cells[13], "Количество изменение")?,
-                value_delta: parse_money_or_zero(&cells[14], "Стоимость изменение")?,
-                planned_in_qty: parse_money_or_zero(&cells[15], "Плановые зачисления")?,
-                planned_out_qty: parse_money_or_zero(&cells[16], "Плановые списания")?,
-                planned_end_qty: parse_money_or_zero(&cells[17], "Плановый исходящий остаток")?,
-            };
+        if let Some(grid) = table {
+            let columns =
+                ColumnMap::resolve(&grid, TABLE_PORTFOLIO, &PORTFOLIO_COLUMNS, mode, warnings)?;
+            for body_row in columns.body_rows(&grid, mode, warnings, is_venue_row)? {
+                let row = match body_row {
+                    BodyRow::Data(_, row) => row,
+                    BodyRow::Marker(cells) => {
+                        // Разделитель блоков по рынкам.
+                        if let Some(m) = current_market.take() {
+                            markets.push(m);
+                        }
+                        current_market = Some(PortfolioMarket::new(venue_name(cells), Vec::new()));
+                        continue;
+                    }
+                    BodyRow::Total(_) => continue,
+                };
+                let position = SecurityPosition {
+                    name: row.string("Наименование")?,
+                    isin: row.string("ISIN")?,
+                    price_currency: row.string("Валюта")?,
+                    kind: classify_security(None, row.text("Наименование")?, row.text("ISIN")?).0,
+                    qty_start: row.money("Количество начало")?,
+                    nominal_start: row.money("Номинал начало")?,
+                    price_start: row.money("Цена начало")?,
+                    value_start_no_ai: row.money("Стоимость без НКД начало")?,
+                    accrued_interest_start: row.money("НКД начало")?,
+                    qty_end: row.money("Количество конец")?,
+                    nominal_end: row.money("Номинал конец")?,
+                    price_end: row.money("Цена конец")?,
+                    price_date: None,
+                    value_end_no_ai: row.money("Стоимость без НКД конец")?,
+                    accrued_interest_end: row.money("НКД конец")?,
+                    qty_delta: row.money("Количество изменение")?,
+                    value_delta: row.money("Стоимость изменение")?,
+                    planned_in_qty: row.money("Плановые зачисления")?,
+                    planned_out_qty: row.money("Плановые списания")?,
+                    planned_end_qty: row.money("Плановый исходящий остаток")?,
+                    qty_blocked: Decimal::ZERO,
+                };
 
-            if let Some(market) = current_market.as_mut() {
-                market.positions.push(position);
-            } else {
-                current_market = Some(PortfolioMarket {
-                    name: "Неизвестно".to_string(),
-                    positions: vec![position],
-                });
+                if let Some(market) = current_market.as_mut() {
+                    market.positions.push(position);
+                } else {
+                    current_market = Some(PortfolioMarket::new(
+                        "Неизвестно".to_string(),
+                        vec![position],
+                    ));
+                }
             }
         }
 
         if let Some(market) = current_market {
             markets.push(market);
         }
+        markets.extend(registrar);
 
         Ok(Portfolio::new(markets))
     }
 
+    /// Парсит паи ПИФ, учитываемые в реестре владельцев, в отдельный блок портфеля.
+    ///
+    /// Поля, которых нет в таблице реестра (номинал, цена и стоимость на начало, НКД,
+    /// изменение стоимости), заполняются нулями — см. [`PortfolioMarketKind::Registrar`].
+    ///
+    /// Возвращает `None`, если такой таблицы в отчёте нет.
+    fn parse_registrar_holdings(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<Option<PortfolioMarket>, ReportError> {
+        let Some(grid) = self.table_grid(&REGISTRAR_HEADERS) else {
+            return Ok(None);
+        };
+
+        let mut positions = Vec::new();
+        let columns =
+            ColumnMap::resolve(&grid, TABLE_PORTFOLIO, &REGISTRAR_COLUMNS, mode, warnings)?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            let qty_start = row.money("Количество паёв начало")?;
+            let qty_end = row.money("Количество паёв конец")?;
+            let kind = match classify_security(None, row.text("Наименование")?, row.text("ISIN")?).0
+            {
+                SecurityKind::Unknown => SecurityKind::FundUnit,
+                kind => kind,
+            };
+            positions.push(SecurityPosition {
+                name: row.string("Наименование")?,
+                isin: row.string("ISIN")?,
+                price_currency: row.string("Валюта")?,
+                kind,
+                qty_start,
+                nominal_start: Decimal::ZERO,
+                price_start: Decimal::ZERO,
+                value_start_no_ai: Decimal::ZERO,
+                accrued_interest_start: Decimal::ZERO,
+                qty_end,
+                nominal_end: Decimal::ZERO,
+                price_end: row.money("Расчетная стоимость пая")?,
+                price_date: row.optional_date("Дата расчета стоимости пая")?,
+                value_end_no_ai: row.money("Стоимость на конец")?,
+                accrued_interest_end: Decimal::ZERO,
+                qty_delta: qty_end - qty_start,
+                value_delta: Decimal::ZERO,
+                planned_in_qty: Decimal::ZERO,
+                planned_out_qty: Decimal::ZERO,
+                planned_end_qty: qty_end,
+                qty_blocked: Decimal::ZERO,
+            });
+        }
+
+        Ok(Some(
+            PortfolioMarket::new("Реестр владельцев паёв".to_string(), positions)
+                .with_kind(PortfolioMarketKind::Registrar),
+        ))
+    }
+
     /// Парсит таблицу пополнений ИИС, если она есть в отчёте.
     ///
     /// # Errors
@@ -349,162 +880,1319 @@ impl DomReport {
         mode: ParseMode,
         warnings: &mut Vec<ParseWarning>,
     ) -> Result<IisContributionsTable, ReportError> {
-        let table = find_table_with_headers(
-            &self.doc,
-            &[
-                "Год",
-                "Лимит, руб.",
-                "Дата операции",
-                "Сумма, руб.",
-                "Основание операции",
-                "Остаток лимита",
-            ],
-            None,
-        )
-        .ok_or(ReportError::TableNotFound { table: TABLE_IIS })?;
+        let grid = self
+            .table_grid(&IIS_HEADERS)
+            .ok_or(ReportError::TableNotFound { table: TABLE_IIS })?;
 
         let mut rows = Vec::new();
-        // Год и лимит могут быть только в первой строке блока.
+        // Объединённые ячейки года и лимита развёрнуты сеткой; пустые ячейки наследуют
+        // значение из предыдущей строки блока.
         let mut current_year: Option<i32> = None;
         let mut current_limit: Option<IisLimit> = None;
 
-        for (idx, tr) in table.select(&TR_SELECTOR).enumerate() {
-            if idx < 3 {
-                continue;
-            }
-            let cells = row_cells(tr);
-            if cells.len() < 6 {
-                ensure_min_cells(TABLE_IIS, idx, cells.len(), 6, mode, warnings)?;
-                continue;
-            }
-            if cells.iter().all(String::is_empty) {
+        let columns = ColumnMap::resolve(&grid, TABLE_IIS, &IIS_COLUMNS, mode, warnings)?;
+        // Подзаголовки блоков на всю ширину таблицы («ИИС3») — служебные строки.
+        for body_row in columns.body_rows(&grid, mode, warnings, GridRow::is_full_width)? {
+            let BodyRow::Data(_, row) = body_row else {
                 continue;
-            }
-            if !cells[0].is_empty() {
+            };
+            let year_cell = row.text("Год")?;
+            if !year_cell.is_empty() {
                 current_year =
                     Some(
-                        cells[0]
+                        year_cell
                             .trim()
                             .parse::<i32>()
                             .map_err(|_| ReportError::Number {
-                                value: cells[0].clone(),
+                                value: year_cell.to_string(),
                                 column: "Год",
                             })?,
                     );
             }
-            if !cells[1].is_empty() {
-                current_limit = Some(parse_iis_limit(&cells[1], "Лимит ИИС")?);
+            let limit_cell = row.text("Лимит ИИС")?;
+            if !limit_cell.is_empty() {
+                current_limit = Some(parse_iis_limit(limit_cell, "Лимит ИИС")?);
             }
-            if cells[2].is_empty() {
+            if row.text("Дата операции")?.is_empty() {
                 continue;
             }
 
             let year = current_year.ok_or(ReportError::MissingField { field: "Год" })?;
             let limit = current_limit.unwrap_or(IisLimit::Amount(Decimal::ZERO));
-            let date = parse_date(&cells[2])?;
-            let amount = parse_money_or_zero(&cells[3], "Сумма ИИС")?;
-            let remaining_limit = parse_iis_limit(&cells[5], "Остаток лимита")?;
+            let date = row.date("Дата операции")?;
+            let amount = row.money("Сумма ИИС")?;
+            let remaining_limit = parse_iis_limit(row.text("Остаток лимита")?, "Остаток лимита")?;
 
             rows.push(IisContribution {
                 year,
                 limit_rub: limit,
                 date,
                 amount,
-                operation_reason: cells[4].clone(),
+                operation_reason: row.string("Основание операции")?,
                 remaining_limit,
             });
         }
 
         Ok(IisContributionsTable::new(rows))
     }
-}
 
-/// Классифицирует строку сводки ДС по известным типам.
-fn classify_cash_flow(description: &str) -> CashFlowKind {
-    let lower = description.to_lowercase();
-    CASH_FLOW_RULES
-        .iter()
-        .find_map(|(needle, kind)| lower.contains(needle).then_some(*kind))
-        .unwrap_or(CashFlowKind::Unknown)
-}
+    /// Парсит таблицу «Сделки купли/продажи ценных бумаг».
+    ///
+    /// ISIN подставляется из справочника ценных бумаг по коду бумаги, если справочник есть в отчёте.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_trades(&self) -> Result<TradesTable, ReportError> {
+        let reference = match self.parse_security_reference() {
+            Ok(reference) => Some(reference),
+            Err(ReportError::TableNotFound { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        let mut ignored_warnings = Vec::new();
+        self.parse_trades_with_mode(
+            ParseMode::Lenient,
+            reference.as_ref(),
+            &mut ignored_warnings,
+        )
+    }
 
-fn parse_capture_date(
-    captures: &regex::Captures<'_>,
-    index: usize,
-    original_text: &str,
-) -> Result<chrono::NaiveDate, ReportError> {
-    let value = captures
-        .get(index)
-        .ok_or_else(|| ReportError::Regex(original_text.to_string()))?
-        .as_str();
-    parse_date(value)
-}
+    pub(crate) fn parse_trades_with_mode(
+        &self,
+        mode: ParseMode,
+        reference: Option<&SecurityReference>,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<TradesTable, ReportError> {
+        let grid = self
+            .table_grid(&TRADES_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_TRADES,
+            })?;
 
-#[allow(clippy::missing_const_for_fn)]
-fn ensure_min_cells(
-    table: &'static str,
-    row_index: usize,
-    actual_cells: usize,
-    expected_cells: usize,
-    mode: ParseMode,
-    warnings: &mut Vec<ParseWarning>,
-) -> Result<(), ReportError> {
-    if mode.is_strict() {
-        return Err(ReportError::MalformedRow {
-            table,
-            row_index,
-            expected_cells,
-            actual_cells,
-        });
+        let mut rows = Vec::new();
+        let mut venue = "Неизвестно".to_string();
+
+        let columns = ColumnMap::resolve(&grid, TABLE_TRADES, &TRADES_COLUMNS, mode, warnings)?;
+        for body_row in columns.body_rows(&grid, mode, warnings, is_venue_row)? {
+            let (idx, row) = match body_row {
+                BodyRow::Data(idx, row) => (idx, row),
+                BodyRow::Marker(cells) => {
+                    venue = venue_name(cells);
+                    continue;
+                }
+                BodyRow::Total(_) => continue,
+            };
+            let code = row.text("Код ЦБ")?;
+
+            rows.push(Trade {
+                trade_date: row.date("Дата заключения")?,
+                settlement_date: row.date("Дата расчетов")?,
+                trade_time: row.optional_time("Время заключения")?,
+                venue: venue.clone(),
+                name: row.string("Наименование ЦБ")?,
+                isin: reference
+                    .and_then(|reference| reference.isin_by_code(code))
+                    .map(str::to_string),
+                code: code.to_string(),
+                currency: row.string("Валюта")?,
+                side: recognize_value(
+                    parse_trade_side(row.text("Вид")?, "Вид"),
+                    TradeSide::Unknown,
+                    TABLE_TRADES,
+                    idx,
+                    mode,
+                    warnings,
+                )?,
+                quantity: row.money("Количество")?,
+                price: row.money("Цена")?,
+                amount: row.money("Сумма")?,
+                accrued_interest: row.money("НКД")?,
+                broker_fee: row.money("Комиссия Брокера")?,
+                exchange_fee: row.money("Комиссия Биржи")?,
+                trade_number: row.string("Номер сделки")?,
+                comment: row.string("Комментарий")?,
+                status: row.string("Статус сделки")?,
+            });
+        }
+
+        Ok(TradesTable::new(rows))
     }
-    warnings.push(ParseWarning::MalformedRow {
-        table,
-        row_index,
-        expected_cells,
-        actual_cells,
-    });
-    Ok(())
-}
 
-fn parse_iis_limit(value: &str, column: &'static str) -> Result<IisLimit, ReportError> {
-    if value.to_lowercase().contains("ограничений нет") {
-        Ok(IisLimit::Unlimited)
-    } else {
-        parse_money_or_zero(value, column).map(IisLimit::Amount)
+    /// Парсит таблицу «Движение ценных бумаг».
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_security_movements(&self) -> Result<SecurityMovementsTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_security_movements_with_mode(ParseMode::Lenient, &mut ignored_warnings)
     }
-}
 
-fn row_cells(tr: ElementRef<'_>) -> Vec<String> {
-    tr.select(&TD_SELECTOR).map(collect_text).collect()
-}
+    pub(crate) fn parse_security_movements_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<SecurityMovementsTable, ReportError> {
+        let grid =
+            self.table_grid(&SECURITY_MOVEMENTS_HEADERS)
+                .ok_or(ReportError::TableNotFound {
+                    table: TABLE_SECURITY_MOVEMENTS,
+                })?;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rust_decimal::Decimal;
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_SECURITY_MOVEMENTS,
+            &SECURITY_MOVEMENTS_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(SecurityMovement {
+                date: row.date("Дата")?,
+                venue: row.string("Торговая площадка")?,
+                name: row.string("Наименование ЦБ")?,
+                isin: row.string("ISIN")?,
+                kind: classify_security_movement(
+                    row.text("Вид операции")?,
+                    row.text("Основание операции")?,
+                ),
+                operation: row.string("Вид операции")?,
+                qty_in: row.money("Зачислено")?,
+                qty_out: row.money("Списано")?,
+                reason: row.string("Основание операции")?,
+                acquisition: None,
+            });
+        }
 
-    #[test]
-    fn classify_cash_flow_matches_known_labels() {
-        assert_eq!(
-            classify_cash_flow("Входящий остаток денежных средств"),
-            CashFlowKind::OpeningBalance
-        );
-        assert_eq!(
-            classify_cash_flow("Комиссия брокера, удержанная за операции"),
-            CashFlowKind::BrokerFee
-        );
-        assert_eq!(
-            classify_cash_flow("исходящий остаток"),
-            CashFlowKind::ClosingBalance
-        );
+        Ok(SecurityMovementsTable::new(rows))
     }
 
-    #[test]
-    fn classify_cash_flow_unknown_for_unmatched_description() {
-        assert_eq!(
-            classify_cash_flow("Произвольная строка"),
-            CashFlowKind::Unknown
-        );
+    /// Парсит таблицу «Сделки с валютными инструментами».
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_fx_trades(&self) -> Result<FxTradesTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_fx_trades_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_fx_trades_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<FxTradesTable, ReportError> {
+        let grid = self
+            .table_grid(&FX_TRADES_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_FX_TRADES,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns =
+            ColumnMap::resolve(&grid, TABLE_FX_TRADES, &FX_TRADES_COLUMNS, mode, warnings)?;
+        // Итоговые строки «Оборот по сделкам купли-продажи, CNY/RUB» — служебные.
+        let is_turnover = |row: &GridRow| row.cell(0).to_lowercase().starts_with("оборот");
+        for body_row in columns.body_rows(&grid, mode, warnings, is_turnover)? {
+            let BodyRow::Data(idx, row) = body_row else {
+                continue;
+            };
+
+            rows.push(FxTrade {
+                pair: parse_currency_pair(row.text("Валютный инструмент")?, "Валютный инструмент")?,
+                instrument: row.string("Валютный инструмент")?,
+                trade_date: row.date("Дата заключения")?,
+                settlement_date: row.date("Дата расчетов")?,
+                trade_time: row.optional_time("Время заключения")?,
+                side: recognize_value(
+                    parse_trade_side(row.text("Вид")?, "Вид"),
+                    TradeSide::Unknown,
+                    TABLE_FX_TRADES,
+                    idx,
+                    mode,
+                    warnings,
+                )?,
+                quantity: row.money("Количество базовой валюты")?,
+                rate: row.money("Цена")?,
+                amount: row.money("Сумма сделки")?,
+                broker_fee: row.money("Комиссия Брокера")?,
+                exchange_fee: row.money("Комиссия Биржи")?,
+                trade_number: row.string("Номер сделки")?,
+                comment: row.string("Комментарий")?,
+            });
+        }
+
+        Ok(FxTradesTable::new(rows))
+    }
+
+    /// Парсит таблицу выплат дивидендов, купонов и погашений с удержанным налогом.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_income_events(&self) -> Result<IncomeEventsTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_income_events_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_income_events_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<IncomeEventsTable, ReportError> {
+        let grid = self
+            .table_grid(&INCOME_EVENTS_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_INCOME_EVENTS,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_INCOME_EVENTS,
+            &INCOME_EVENTS_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(IncomeEvent {
+                payment_date: row.date("Дата выплаты")?,
+                name: row.string("Наименование ЦБ")?,
+                isin: row.string("ISIN")?,
+                kind: classify_income(row.text("Вид выплаты")?),
+                description: row.string("Вид выплаты")?,
+                gross: row.money("Сумма до налогообложения")?,
+                tax_withheld: row.money("Удержанный налог")?,
+                net: row.money("Сумма к выплате")?,
+                currency: row.string("Валюта")?,
+            });
+        }
+
+        Ok(IncomeEventsTable::new(rows))
+    }
+
+    /// Парсит налоговый блок отчёта: базу, исчисленный и удержанный НДФЛ по кодам дохода.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_tax_summary(&self) -> Result<TaxSummary, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_tax_summary_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_tax_summary_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<TaxSummary, ReportError> {
+        let grid = self
+            .table_grid(&TAX_SUMMARY_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_TAX_SUMMARY,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_TAX_SUMMARY,
+            &TAX_SUMMARY_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(TaxRow {
+                income_code: row.string("Код дохода")?,
+                rate: parse_money_or_zero(
+                    row.text("Ставка налога")?.trim_end_matches('%'),
+                    "Ставка налога",
+                )?,
+                tax_base: row.money("Налоговая база")?,
+                calculated: row.money("Исчисленный налог")?,
+                withheld: row.money("Удержанный налог")?,
+                due: row.money("Налог к удержанию")?,
+            });
+        }
+
+        Ok(TaxSummary::new(rows))
+    }
+
+    /// Парсит таблицу сделок РЕПО с обеими частями сделки.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_repo_deals(&self) -> Result<RepoDealsTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_repo_deals_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_repo_deals_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<RepoDealsTable, ReportError> {
+        let grid = self
+            .table_grid(&REPO_DEALS_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_REPO_DEALS,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns =
+            ColumnMap::resolve(&grid, TABLE_REPO_DEALS, &REPO_DEALS_COLUMNS, mode, warnings)?;
+        for (idx, row) in columns.data_rows(&grid, mode, warnings)? {
+            let side = recognize_value(
+                parse_trade_side(row.text("Вид")?, "Вид"),
+                TradeSide::Unknown,
+                TABLE_REPO_DEALS,
+                idx,
+                mode,
+                warnings,
+            )?;
+            let direction = match side {
+                TradeSide::Sell => RepoDirection::Direct,
+                TradeSide::Buy => RepoDirection::Reverse,
+                TradeSide::Unknown => RepoDirection::Unknown,
+            };
+
+            rows.push(RepoDeal {
+                trade_date: row.date("Дата заключения")?,
+                first_leg: RepoLeg {
+                    date: row.optional_date("Дата исполнения 1 части")?,
+                    price: row.money("Цена 1 части")?,
+                    amount: row.money("Сумма 1 части")?,
+                },
+                second_leg: RepoLeg {
+                    date: row.optional_date("Дата исполнения 2 части")?,
+                    price: row.money("Цена 2 части")?,
+                    amount: row.money("Сумма 2 части")?,
+                },
+                name: row.string("Наименование ЦБ")?,
+                isin: row.string("ISIN")?,
+                direction,
+                quantity: row.money("Количество")?,
+                rate: row.money("Ставка РЕПО")?,
+                income: row.money("Доход по сделке")?,
+                currency: row.string("Валюта")?,
+                trade_number: row.string("Номер сделки")?,
+            });
+        }
+
+        Ok(RepoDealsTable::new(rows))
+    }
+
+    /// Парсит секцию срочного рынка: открытые позиции и вариационную маржу.
+    ///
+    /// Достаточно наличия одной из двух таблиц; отсутствующая даёт пустой список.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если обе таблицы отсутствуют или в строках встречены невалидные значения.
+    pub fn parse_derivatives(&self) -> Result<DerivativesSection, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_derivatives_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_derivatives_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<DerivativesSection, ReportError> {
+        let positions_table = self.table_grid(&DERIVATIVE_POSITIONS_HEADERS);
+        let margin_table = self.table_grid(&VARIATION_MARGIN_HEADERS);
+        if positions_table.is_none() && margin_table.is_none() {
+            return Err(ReportError::TableNotFound {
+                table: TABLE_DERIVATIVES,
+            });
+        }
+
+        let positions = positions_table
+            .map(|grid| parse_derivative_positions(&grid, mode, warnings))
+            .transpose()?
+            .unwrap_or_default();
+        let variation_margin = margin_table
+            .map(|grid| parse_variation_margin(&grid, mode, warnings))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(DerivativesSection::new(positions, variation_margin))
+    }
+
+    /// Парсит незавершённые расчёты: плановые движения из таблицы «Денежные средства»
+    /// и «Требования и Обязательства на Валютном рынке».
+    ///
+    /// Строки без плановых движений в таблице «Денежные средства» пропускаются.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если обе таблицы отсутствуют или в строках встречены невалидные значения.
+    pub fn parse_pending_settlements(&self) -> Result<PendingSettlements, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_pending_settlements_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_pending_settlements_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<PendingSettlements, ReportError> {
+        let cash_table = self.table_grid(&PENDING_CASH_HEADERS);
+        let fx_table = self.table_grid(&PENDING_FX_HEADERS);
+        if cash_table.is_none() && fx_table.is_none() {
+            return Err(ReportError::TableNotFound {
+                table: TABLE_PENDING_SETTLEMENTS,
+            });
+        }
+
+        let mut rows = Vec::new();
+        if let Some(grid) = cash_table {
+            rows.extend(parse_pending_cash(&grid, mode, warnings)?);
+        }
+        if let Some(grid) = fx_table {
+            rows.extend(parse_pending_fx(&grid, mode, warnings)?);
+        }
+
+        Ok(PendingSettlements::new(rows))
+    }
+
+    /// Парсит таблицу необеспеченных позиций: займы денежных средств и короткие позиции.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_margin(&self) -> Result<MarginSection, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_margin_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_margin_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<MarginSection, ReportError> {
+        let grid = self
+            .table_grid(&MARGIN_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_MARGIN,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(&grid, TABLE_MARGIN, &MARGIN_COLUMNS, mode, warnings)?;
+        for (idx, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(MarginPosition {
+                instrument: row.string("Инструмент")?,
+                kind: recognize_value(
+                    parse_margin_exposure_kind(row.text("Тип задолженности")?),
+                    MarginExposureKind::Unknown,
+                    TABLE_MARGIN,
+                    idx,
+                    mode,
+                    warnings,
+                )?,
+                quantity: row.money("Количество")?,
+                valuation: row.money("Оценка задолженности")?,
+                rate: row.money("Ставка")?,
+                fee: row.money("Плата за период")?,
+                currency: row.string("Валюта")?,
+            });
+        }
+
+        Ok(MarginSection::new(rows))
+    }
+
+    /// Парсит таблицу заблокированных и обременённых ценных бумаг.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_blocked_holdings(&self) -> Result<BlockedHoldingsTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_blocked_holdings_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_blocked_holdings_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<BlockedHoldingsTable, ReportError> {
+        let grid =
+            self.table_grid(&BLOCKED_HOLDINGS_HEADERS)
+                .ok_or(ReportError::TableNotFound {
+                    table: TABLE_BLOCKED_HOLDINGS,
+                })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_BLOCKED_HOLDINGS,
+            &BLOCKED_HOLDINGS_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(BlockedHolding {
+                name: row.string("Наименование")?,
+                isin: row.string("ISIN")?,
+                quantity: row.money("Количество")?,
+                valuation: row.money("Оценка")?,
+                currency: row.string("Валюта")?,
+                reason: classify_block_reason(row.text("Причина блокировки")?),
+                reason_raw: row.string("Причина блокировки")?,
+            });
+        }
+
+        Ok(BlockedHoldingsTable::new(rows))
+    }
+
+    /// Парсит внебиржевые сделки и переводы бумаг с заявленной стоимостью приобретения.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или в строках встречены невалидные значения.
+    pub fn parse_otc_operations(&self) -> Result<OtcOperationsTable, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_otc_operations_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_otc_operations_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<OtcOperationsTable, ReportError> {
+        let grid = self
+            .table_grid(&OTC_OPERATIONS_HEADERS)
+            .ok_or(ReportError::TableNotFound {
+                table: TABLE_OTC_OPERATIONS,
+            })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_OTC_OPERATIONS,
+            &OTC_OPERATIONS_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (idx, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(OtcOperation {
+                date: row.date("Дата")?,
+                name: row.string("Наименование ЦБ")?,
+                isin: row.string("ISIN")?,
+                kind: classify_security_movement(
+                    row.text("Вид операции")?,
+                    row.text("Контрагент")?,
+                ),
+                direction: recognize_value(
+                    parse_otc_direction(row.text("Вид операции")?),
+                    OtcDirection::Unknown,
+                    TABLE_OTC_OPERATIONS,
+                    idx,
+                    mode,
+                    warnings,
+                )?,
+                operation: row.string("Вид операции")?,
+                quantity: row.money("Количество")?,
+                acquisition: AcquisitionCost {
+                    price: row.money("Цена приобретения")?,
+                    cost: row.money("Стоимость приобретения")?,
+                    date: row.optional_date("Дата приобретения")?,
+                    currency: row.string("Валюта")?,
+                },
+                counterparty: row.string("Контрагент")?,
+            });
+        }
+
+        Ok(OtcOperationsTable::new(rows))
+    }
+
+    /// Парсит таблицу «Справочник ценных бумаг».
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если таблица отсутствует или строки не соответствуют ожидаемому формату.
+    pub fn parse_security_reference(&self) -> Result<SecurityReference, ReportError> {
+        let mut ignored_warnings = Vec::new();
+        self.parse_security_reference_with_mode(ParseMode::Lenient, &mut ignored_warnings)
+    }
+
+    pub(crate) fn parse_security_reference_with_mode(
+        &self,
+        mode: ParseMode,
+        warnings: &mut Vec<ParseWarning>,
+    ) -> Result<SecurityReference, ReportError> {
+        let grid =
+            self.table_grid(&SECURITY_REFERENCE_HEADERS)
+                .ok_or(ReportError::TableNotFound {
+                    table: TABLE_SECURITY_REFERENCE,
+                })?;
+
+        let mut rows = Vec::new();
+        let columns = ColumnMap::resolve(
+            &grid,
+            TABLE_SECURITY_REFERENCE,
+            &SECURITY_REFERENCE_COLUMNS,
+            mode,
+            warnings,
+        )?;
+        for (_, row) in columns.data_rows(&grid, mode, warnings)? {
+            rows.push(SecurityInfo {
+                name: row.string("Наименование")?,
+                code: row.string("Код")?,
+                isin: row.string("ISIN")?,
+                issuer: row.string("Эмитент")?,
+                instrument_type: row.string("Вид")?,
+                registration_number: row.string("Выпуск")?,
+                face_value_currency: Some(row.string("Валюта номинала")?)
+                    .filter(|value| !value.is_empty()),
+            });
+        }
+
+        Ok(SecurityReference::new(rows))
+    }
+
+    /// Добавляет предупреждения о таблицах, которые не относятся ни к одной известной секции.
+    ///
+    /// Служебные таблицы без строки `table-header` (подписи, реквизиты) не учитываются.
+    pub(crate) fn report_unrecognized_tables(&self, warnings: &mut Vec<ParseWarning>) {
+        for table in self.tables() {
+            let is_data_table = table
+                .header_rows()
+                .first()
+                .and_then(GridRow::class)
+                .is_some_and(|class| class.split_whitespace().any(|item| item == "table-header"));
+            if !is_data_table
+                || KNOWN_TABLE_HEADERS
+                    .iter()
+                    .any(|phrases| table.has_headers(phrases))
+            {
+                continue;
+            }
+            warnings.push(ParseWarning::UnrecognizedTable {
+                heading: table.heading().map(str::to_string),
+                header_cells: table
+                    .grid()
+                    .header_paths()
+                    .into_iter()
+                    .map(|path| path.join(" / "))
+                    .collect(),
+            });
+        }
+    }
+}
+
+/// Разбирает таблицу открытых позиций по срочным контрактам.
+fn parse_derivative_positions(
+    grid: &TableGrid,
+    mode: ParseMode,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<DerivativePosition>, ReportError> {
+    let columns = ColumnMap::resolve(
+        grid,
+        TABLE_DERIVATIVES,
+        &DERIVATIVE_POSITIONS_COLUMNS,
+        mode,
+        warnings,
+    )?;
+    let mut positions = Vec::new();
+    for (_, row) in columns.data_rows(grid, mode, warnings)? {
+        positions.push(DerivativePosition {
+            contract_code: row.string("Код контракта")?,
+            contract_type: row.string("Тип контракта")?,
+            base_asset: row.string("Базовый актив")?,
+            expiration_date: row.optional_date("Дата исполнения")?,
+            qty_start: row.money("Позиция на начало")?,
+            qty_end: row.money("Позиция на конец")?,
+            settlement_price: row.money("Расчетная цена")?,
+            guarantee_collateral: row.money("Гарантийное обеспечение")?,
+            currency: row.string("Валюта")?,
+        });
+    }
+
+    Ok(positions)
+}
+
+/// Разбирает таблицу вариационной маржи по срочным контрактам.
+fn parse_variation_margin(
+    grid: &TableGrid,
+    mode: ParseMode,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<VariationMarginRow>, ReportError> {
+    let columns = ColumnMap::resolve(
+        grid,
+        TABLE_DERIVATIVES,
+        &VARIATION_MARGIN_COLUMNS,
+        mode,
+        warnings,
+    )?;
+    let mut variation_margin = Vec::new();
+    for (_, row) in columns.data_rows(grid, mode, warnings)? {
+        variation_margin.push(VariationMarginRow {
+            date: row.date("Дата")?,
+            contract_code: row.string("Код контракта")?,
+            variation_margin: row.money("Вариационная маржа")?,
+            exchange_fee: row.money("Биржевой сбор")?,
+            currency: row.string("Валюта")?,
+        });
+    }
+
+    Ok(variation_margin)
+}
+
+/// Разбирает плановые зачисления и списания из таблицы «Денежные средства».
+fn parse_pending_cash(
+    grid: &TableGrid,
+    mode: ParseMode,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<PendingSettlement>, ReportError> {
+    let columns = ColumnMap::resolve(
+        grid,
+        TABLE_PENDING_SETTLEMENTS,
+        &PENDING_CASH_COLUMNS,
+        mode,
+        warnings,
+    )?;
+    let mut rows = Vec::new();
+    for (_, row) in columns.data_rows(grid, mode, warnings)? {
+        let incoming = row.money("Плановые зачисления")?;
+        let outgoing = row.money("Плановые списания")?;
+        if incoming.is_zero() && outgoing.is_zero() {
+            continue;
+        }
+        rows.push(PendingSettlement {
+            source: SettlementSource::BrokerCash,
+            venue: Some(row.string("Торговая площадка")?).filter(|venue| !venue.is_empty()),
+            currency: row.string("Валюта")?,
+            settlement_date: None,
+            incoming,
+            outgoing,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Разбирает требования и обязательства на валютном рынке с датами исполнения.
+fn parse_pending_fx(
+    grid: &TableGrid,
+    mode: ParseMode,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<Vec<PendingSettlement>, ReportError> {
+    let columns = ColumnMap::resolve(
+        grid,
+        TABLE_PENDING_SETTLEMENTS,
+        &PENDING_FX_COLUMNS,
+        mode,
+        warnings,
+    )?;
+    let mut rows = Vec::new();
+    let mut settlement_date = None;
+    let is_date_row = |row: &GridRow| row.cell(0).starts_with(SETTLEMENT_DATE_PREFIX);
+    for body_row in columns.body_rows(grid, mode, warnings, is_date_row)? {
+        let row = match body_row {
+            BodyRow::Data(_, row) => row,
+            BodyRow::Marker(cells) => {
+                let date = cells[0].trim_start_matches(SETTLEMENT_DATE_PREFIX);
+                settlement_date = parse_optional_date(date.trim())?;
+                continue;
+            }
+            BodyRow::Total(_) => continue,
+        };
+        rows.push(PendingSettlement {
+            source: SettlementSource::FxMarket,
+            venue: None,
+            currency: row.string("Валюта")?,
+            settlement_date,
+            incoming: row.money("Требования")?,
+            outgoing: row.money("Обязательства")?,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Определяет вид бумаги по справочнику, наименованию и ISIN.
+///
+/// Возвращает выбранный вид и список видов, предложенных каждым из источников. Приоритет:
+/// справочник, затем наименование, затем ISIN.
+fn classify_security(
+    info: Option<&SecurityInfo>,
+    name: &str,
+    isin: &str,
+) -> (SecurityKind, Vec<SecurityKind>) {
+    let by_type = info.and_then(|info| {
+        let lower = info.instrument_type.to_lowercase();
+        SECURITY_TYPE_RULES
+            .iter()
+            .find_map(|(needle, kind)| lower.contains(needle).then_some(*kind))
+    });
+
+    let name_lower = name.to_lowercase();
+    let words: Vec<&str> = name_lower
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let by_name = SECURITY_NAME_RULES
+        .iter()
+        .find_map(|(needle, position, kind)| {
+            name_has_word(&words, needle, *position).then_some(*kind)
+        });
+
+    let by_isin = classify_isin(isin);
+
+    let mut candidates: Vec<SecurityKind> = Vec::new();
+    for kind in [by_type, by_name, by_isin].into_iter().flatten() {
+        if !candidates.contains(&kind) {
+            candidates.push(kind);
+        }
+    }
+    let chosen = candidates.first().copied().unwrap_or(SecurityKind::Unknown);
+    (chosen, candidates)
+}
+
+/// Проверяет, что слово стоит в наименовании на месте, которое требует правило.
+fn name_has_word(words: &[&str], needle: &str, position: NamePosition) -> bool {
+    match position {
+        NamePosition::Anywhere => words.contains(&needle),
+        NamePosition::Last => words.last() == Some(&needle),
+        NamePosition::BeforeSeries => words
+            .windows(2)
+            .any(|pair| pair[0] == needle && pair[1].starts_with(|ch: char| ch.is_ascii_digit())),
+    }
+}
+
+/// Эвристика по префиксу ISIN для тех случаев, где он однозначен.
+///
+/// Российские выпуски (`RU…`) сюда не входят: и акции, и облигации, и паи выпускаются
+/// с префиксом `RU000A`, поэтому их вид берётся из справочника или наименования.
+fn classify_isin(isin: &str) -> Option<SecurityKind> {
+    let isin = isin.trim().to_ascii_uppercase();
+    if isin.len() != 12 {
+        return None;
+    }
+    if isin.starts_with("XS") {
+        // Еврооблигации.
+        Some(SecurityKind::Bond)
+    } else if isin.starts_with("IE00") || isin.starts_with("LU") {
+        // Иностранные UCITS-фонды.
+        Some(SecurityKind::FundUnit)
+    } else {
+        None
+    }
+}
+
+/// Уточняет вид бумаг портфеля по справочнику и фиксирует противоречивые классификации.
+pub(crate) fn classify_positions(
+    portfolio: &mut Portfolio,
+    reference: Option<&SecurityReference>,
+    warnings: &mut Vec<ParseWarning>,
+) {
+    for position in portfolio
+        .markets
+        .iter_mut()
+        .flat_map(|market| market.positions.iter_mut())
+    {
+        let info = reference.and_then(|reference| reference.get(&position.isin));
+        let (kind, candidates) = classify_security(info, &position.name, &position.isin);
+        // Вид, заданный при разборе блока (например, паи у регистратора), не затирается.
+        if kind != SecurityKind::Unknown {
+            position.kind = kind;
+        }
+        if candidates.len() > 1 {
+            warnings.push(ParseWarning::AmbiguousSecurityKind {
+                table: TABLE_PORTFOLIO,
+                isin: position.isin.clone(),
+                chosen: kind,
+                candidates,
+            });
+        }
+    }
+}
+
+/// Переносит в позиции портфеля заблокированное количество из таблицы заблокированных бумаг.
+pub(crate) fn mark_blocked_positions(portfolio: &mut Portfolio, blocked: &BlockedHoldingsTable) {
+    for position in portfolio
+        .markets
+        .iter_mut()
+        .flat_map(|market| market.positions.iter_mut())
+    {
+        position.qty_blocked = blocked.quantity_for(&position.isin);
+    }
+}
+
+/// Переносит заявленную стоимость приобретения из внебиржевых операций в движение ЦБ.
+pub(crate) fn attach_acquisition_costs(
+    movements: &mut SecurityMovementsTable,
+    otc: &OtcOperationsTable,
+) {
+    let costs: Vec<_> = otc
+        .match_movements(&movements.rows)
+        .into_iter()
+        .map(|operation| operation.map(|operation| operation.acquisition.clone()))
+        .collect();
+    for (movement, acquisition) in movements.rows.iter_mut().zip(costs) {
+        movement.acquisition = acquisition;
+    }
+}
+
+/// Классифицирует причину блокировки по её описанию.
+fn classify_block_reason(description: &str) -> BlockReason {
+    let lower = description.to_lowercase();
+    BLOCK_REASON_RULES
+        .iter()
+        .find_map(|(needle, reason)| lower.contains(needle).then_some(*reason))
+        .unwrap_or(BlockReason::Unknown)
+}
+
+/// Классифицирует строку сводки ДС по известным типам.
+fn classify_cash_flow(description: &str) -> CashFlowKind {
+    let lower = description.to_lowercase();
+    CASH_FLOW_RULES
+        .iter()
+        .find_map(|(needle, kind)| lower.contains(needle).then_some(*kind))
+        .unwrap_or(CashFlowKind::Unknown)
+}
+
+/// Классифицирует вид выплаты дохода по описанию.
+fn classify_income(description: &str) -> IncomeKind {
+    let lower = description.to_lowercase();
+    INCOME_RULES
+        .iter()
+        .find_map(|(needle, kind)| lower.contains(needle).then_some(*kind))
+        .unwrap_or(IncomeKind::Unknown)
+}
+
+/// Классифицирует операцию движения ЦБ по названию операции и её основанию.
+fn classify_security_movement(operation: &str, reason: &str) -> SecurityMovementKind {
+    let lower = format!("{operation} {reason}").to_lowercase();
+    SECURITY_MOVEMENT_RULES
+        .iter()
+        .find_map(|(needle, kind)| lower.contains(needle).then_some(*kind))
+        .unwrap_or(SecurityMovementKind::Unknown)
+}
+
+fn parse_capture_date(
+    captures: &regex::Captures<'_>,
+    index: usize,
+    original_text: &str,
+) -> Result<chrono::NaiveDate, ReportError> {
+    let value = captures
+        .get(index)
+        .ok_or_else(|| ReportError::Regex(original_text.to_string()))?
+        .as_str();
+    parse_date(value)
+}
+
+/// Разделитель блоков по торговым площадкам: «Площадка: …».
+fn is_venue_row(row: &GridRow) -> bool {
+    row.cell(0).starts_with("Площадка")
+}
+
+fn venue_name(cells: &[String]) -> String {
+    cells[0].trim_start_matches("Площадка:").trim().to_string()
+}
+
+/// Заменяет нераспознанное значение ячейки вариантом `unknown`: в строгом режиме ошибка
+/// [`ReportError::UnexpectedValue`] возвращается как есть, в мягком — становится предупреждением.
+fn recognize_value<T>(
+    parsed: Result<T, ReportError>,
+    unknown: T,
+    table: &'static str,
+    row_index: usize,
+    mode: ParseMode,
+    warnings: &mut Vec<ParseWarning>,
+) -> Result<T, ReportError> {
+    match parsed {
+        Err(ReportError::UnexpectedValue { value, column }) if !mode.is_strict() => {
+            warnings.push(ParseWarning::UnexpectedValue {
+                table,
+                row_index,
+                column,
+                value,
+            });
+            Ok(unknown)
+        }
+        parsed => parsed,
+    }
+}
+
+fn parse_trade_side(value: &str, column: &'static str) -> Result<TradeSide, ReportError> {
+    let lower = value.to_lowercase();
+    if lower.starts_with("покупка") {
+        Ok(TradeSide::Buy)
+    } else if lower.starts_with("продажа") {
+        Ok(TradeSide::Sell)
+    } else {
+        Err(ReportError::UnexpectedValue {
+            value: value.trim().to_string(),
+            column,
+        })
+    }
+}
+
+/// Разбирает код валютного инструмента: `CNYRUB`, `CNYRUB_TOM`, `EUR_RUB__TOM`, `CNY/RUB`.
+///
+/// Для кодов вида `USD000UTSTOM` сопряжённая валюта не указана явно и считается рублём.
+fn parse_currency_pair(value: &str, column: &'static str) -> Result<CurrencyPair, ReportError> {
+    let invalid = || ReportError::UnexpectedValue {
+        value: value.trim().to_string(),
+        column,
+    };
+    let tokens: Vec<String> = value
+        .split(|ch: char| !ch.is_ascii_alphabetic())
+        .filter(|token| !token.is_empty())
+        .map(str::to_ascii_uppercase)
+        .collect();
+    let first = tokens.first().ok_or_else(invalid)?;
+    if first.len() < 3 {
+        return Err(invalid());
+    }
+
+    let base = first[..3].to_string();
+    let quote = if first.len() == 6 {
+        first[3..].to_string()
+    } else {
+        tokens
+            .get(1)
+            .filter(|token| first.len() == 3 && token.len() == 3 && token.as_str() != "TOM")
+            .cloned()
+            .unwrap_or_else(|| "RUB".to_string())
+    };
+    Ok(CurrencyPair { base, quote })
+}
+
+fn parse_iis_limit(value: &str, column: &'static str) -> Result<IisLimit, ReportError> {
+    if value.to_lowercase().contains("ограничений нет") {
+        Ok(IisLimit::Unlimited)
+    } else {
+        parse_money_or_zero(value, column).map(IisLimit::Amount)
+    }
+}
+
+fn parse_otc_direction(value: &str) -> Result<OtcDirection, ReportError> {
+    let lower = value.to_lowercase();
+    if lower.contains("зачисл") || lower.contains("покуп") {
+        Ok(OtcDirection::Incoming)
+    } else if lower.contains("списан") || lower.contains("продаж") {
+        Ok(OtcDirection::Outgoing)
+    } else {
+        Err(ReportError::UnexpectedValue {
+            value: value.to_string(),
+            column: "Вид операции",
+        })
+    }
+}
+
+fn parse_margin_exposure_kind(value: &str) -> Result<MarginExposureKind, ReportError> {
+    let lower = value.to_lowercase();
+    if lower.contains("денеж") {
+        Ok(MarginExposureKind::BorrowedCash)
+    } else if lower.contains("бумаг") {
+        Ok(MarginExposureKind::ShortSecurity)
+    } else {
+        Err(ReportError::UnexpectedValue {
+            value: value.to_string(),
+            column: "Тип задолженности",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn classify_block_reason_matches_known_labels() {
+        assert_eq!(
+            classify_block_reason("Блокировка в Euroclear"),
+            BlockReason::ForeignInfrastructure
+        );
+        assert_eq!(
+            classify_block_reason("Обременение: залог"),
+            BlockReason::Pledge
+        );
+        assert_eq!(
+            classify_block_reason("Арест по постановлению"),
+            BlockReason::Arrest
+        );
+        assert_eq!(classify_block_reason("Прочее"), BlockReason::Unknown);
+    }
+
+    #[test]
+    fn classify_cash_flow_matches_known_labels() {
+        assert_eq!(
+            classify_cash_flow("Входящий остаток денежных средств"),
+            CashFlowKind::OpeningBalance
+        );
+        assert_eq!(
+            classify_cash_flow("Комиссия брокера, удержанная за операции"),
+            CashFlowKind::BrokerFee
+        );
+        assert_eq!(
+            classify_cash_flow("исходящий остаток"),
+            CashFlowKind::ClosingBalance
+        );
+        assert_eq!(
+            classify_cash_flow("Доход по сделкам РЕПО"),
+            CashFlowKind::RepoIncome
+        );
+        assert_eq!(
+            classify_cash_flow("Вариационная маржа"),
+            CashFlowKind::VariationMargin
+        );
+        assert_eq!(
+            classify_cash_flow("Комиссия брокера за перенос необеспеченной позиции"),
+            CashFlowKind::MarginFee
+        );
+    }
+
+    #[test]
+    fn classify_cash_flow_unknown_for_unmatched_description() {
+        assert_eq!(
+            classify_cash_flow("Произвольная строка"),
+            CashFlowKind::Unknown
+        );
+    }
+
+    #[test]
+    fn classify_security_movement_prefers_specific_reason() {
+        assert_eq!(
+            classify_security_movement("Зачисление", "Сделка № 123"),
+            SecurityMovementKind::Trade
+        );
+        assert_eq!(
+            classify_security_movement("Зачисление", "Конвертация по решению эмитента"),
+            SecurityMovementKind::Conversion
+        );
+        assert_eq!(
+            classify_security_movement("Перевод ЦБ", "Поручение клиента"),
+            SecurityMovementKind::Transfer
+        );
+        assert_eq!(
+            classify_security_movement("Списание", "Погашение выпуска"),
+            SecurityMovementKind::Redemption
+        );
+        assert_eq!(
+            classify_security_movement("Зачисление", ""),
+            SecurityMovementKind::Unknown
+        );
+    }
+
+    #[test]
+    fn parse_currency_pair_handles_exchange_codes() {
+        let pair = |value| parse_currency_pair(value, "Инструмент").expect("must parse pair");
+        assert_eq!(pair("CNYRUB").to_string(), "CNY/RUB");
+        assert_eq!(pair("CNYRUB_TOM").to_string(), "CNY/RUB");
+        assert_eq!(pair("EUR_RUB__TOM").to_string(), "EUR/RUB");
+        assert_eq!(pair("USD000UTSTOM").to_string(), "USD/RUB");
+        assert_eq!(pair("cny/rub").to_string(), "CNY/RUB");
+        assert!(parse_currency_pair("--", "Инструмент").is_err());
+    }
+
+    fn info(instrument_type: &str) -> SecurityInfo {
+        SecurityInfo {
+            name: String::new(),
+            code: String::new(),
+            isin: String::new(),
+            issuer: String::new(),
+            instrument_type: instrument_type.to_string(),
+            registration_number: String::new(),
+            face_value_currency: None,
+        }
+    }
+
+    #[test]
+    fn classify_security_prefers_reference_type() {
+        let structured = info("Облигация структурная");
+        assert_eq!(
+            classify_security(Some(&structured), "Нота 1", "RU000A100001"),
+            (
+                SecurityKind::StructuredNote,
+                vec![SecurityKind::StructuredNote]
+            )
+        );
+        let unit = info("Инвестиционный пай");
+        assert_eq!(
+            classify_security(Some(&unit), "GOLD", "RUGOLD").0,
+            SecurityKind::FundUnit
+        );
+    }
+
+    #[test]
+    fn classify_security_falls_back_to_name_and_isin() {
+        assert_eq!(
+            classify_security(None, "ОФЗ 26238", "RU000A1038V6").0,
+            SecurityKind::Bond
+        );
+        assert_eq!(
+            classify_security(None, "Сбербанк ао", "RU0009029540").0,
+            SecurityKind::Share
+        );
+        assert_eq!(
+            classify_security(None, "FinEx Gold ETF", "IE00B8XB7377").0,
+            SecurityKind::FundUnit
+        );
+        assert_eq!(
+            classify_security(None, "Неизвестно", "RUXXXXXXXXXX"),
+            (SecurityKind::Unknown, Vec::new())
+        );
+    }
+
+    #[test]
+    fn classify_security_matches_short_name_tokens_only_in_place() {
+        assert_eq!(
+            classify_security(None, "РЖД БО-001Р-02", "RU000A0JX0J2").0,
+            SecurityKind::Bond
+        );
+        assert_eq!(
+            classify_security(None, "Яндекс ДР", "").0,
+            SecurityKind::DepositaryReceipt
+        );
+        // Сокращения не на своём месте и российский ISIN вид не определяют.
+        assert_eq!(
+            classify_security(None, "Ао Бо Др Групп", "RU0009029540"),
+            (SecurityKind::Unknown, Vec::new())
+        );
+    }
+
+    #[test]
+    fn classify_security_reports_conflicting_sources() {
+        let share = info("Акция обыкновенная");
+        let (kind, candidates) = classify_security(Some(&share), "Облигация БО-01", "");
+        assert_eq!(kind, SecurityKind::Share);
+        assert_eq!(candidates, vec![SecurityKind::Share, SecurityKind::Bond]);
+    }
+
+    #[test]
+    fn classify_income_distinguishes_partial_redemption() {
+        assert_eq!(classify_income("Дивиденды"), IncomeKind::Dividend);
+        assert_eq!(classify_income("Купонный доход"), IncomeKind::Coupon);
+        assert_eq!(
+            classify_income("Частичное погашение номинала"),
+            IncomeKind::PartialAmortization
+        );
+        assert_eq!(classify_income("Погашение"), IncomeKind::Redemption);
+        assert_eq!(classify_income("Выплата"), IncomeKind::Unknown);
+    }
+
+    #[test]
+    fn parse_trade_side_recognizes_buy_and_sell() {
+        assert_eq!(
+            parse_trade_side("Покупка", "Вид").expect("must parse buy"),
+            TradeSide::Buy
+        );
+        assert_eq!(
+            parse_trade_side("продажа", "Вид").expect("must parse sell"),
+            TradeSide::Sell
+        );
+        assert!(matches!(
+            parse_trade_side("Мена", "Вид"),
+            Err(ReportError::UnexpectedValue {
+                column: "Вид", ..
+            })
+        ));
     }
 
     #[test]
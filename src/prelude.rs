@@ -2,8 +2,8 @@
 
 pub use crate::{
     AccountId, AccountKind, AssetValuation, AssetValuationRow, CashFlowKind, CashFlowRow,
-    CashFlowSummary, DomReport, IisContribution, IisContributionsTable, IisLimit, MergedPosition,
-    Money, ParseConfig, ParseMode, ParseWarning, Portfolio, PortfolioMarket, RawReport, Report,
-    ReportBuilder, ReportError, ReportMetadata, ReportSection, ReportSet, SectionSet,
-    SecurityPosition,
+    CashFlowSummary, DomReport, IisContribution, IisContributionsTable, IisLimit, LoadOptions,
+    LoadOutcome, MergedPosition, Money, ParseConfig, ParseMode, ParseWarning, Portfolio,
+    PortfolioMarket, RawReport, Report, ReportBuilder, ReportError, ReportMetadata, ReportSection,
+    ReportSet, SectionSet, SecurityPosition,
 };
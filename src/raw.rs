@@ -1,27 +1,146 @@
 //! Работа с исходным HTML и подготовленным DOM-деревом.
 
+use crate::encoding::{self, TextEncoding};
 use crate::error::ReportError;
-use scraper::Html;
-use std::io::Read;
+use crate::grid::{ReportTable, TableGrid};
+use crate::parser::{ASSET_VALUATION_HEADERS, PERIOD_RE};
+use crate::sources;
+use crate::utils::collect_text;
+use crate::xlsx::{self, SheetDocument};
+use scraper::{ElementRef, Html, Selector};
+use std::io::{Read, Seek};
 use std::str::FromStr;
+use std::sync::LazyLock;
+
+static BLOCK_SELECTOR: LazyLock<Selector> = LazyLock::new(|| {
+    Selector::parse("table, p, h1, h2, h3, h4, h5, h6").expect("valid block selector")
+});
+static CAPTION_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("caption").expect("valid caption selector"));
+static H3_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("h3").expect("valid h3 selector"));
+static HEAD_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("head").expect("valid head selector"));
+static P_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("p").expect("valid p selector"));
+static RATING_SELECTOR: LazyLock<Selector> =
+    LazyLock::new(|| Selector::parse("table.RatingAssets").expect("valid rating selector"));
 
 /// Исходный HTML отчёта без разбора DOM.
 #[derive(Debug, Clone)]
 pub struct RawReport {
-    /// Полный HTML отчёта.
-    pub html: String,
+    /// Полный HTML отчёта; пустая строка для отчёта, прочитанного из XLSX.
+    html: String,
+    /// Кодировка исходных байтов; `None`, если отчёт создан из готовой строки.
+    encoding: Option<TextEncoding>,
+    /// Блоки листов для отчёта, прочитанного из XLSX.
+    sheets: Option<SheetDocument>,
 }
 
 impl RawReport {
     /// Читает HTML-отчёт из произвольного `Read`.
     ///
+    /// Кодировка определяется так же, как в [`RawReport::from_bytes`].
+    ///
     /// # Errors
     ///
     /// Возвращает [`ReportError::Io`], если не удалось прочитать источник.
     pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ReportError> {
-        let mut html = String::new();
-        reader.read_to_string(&mut html)?;
-        Ok(Self { html })
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    /// Декодирует HTML-отчёт из байтов, определяя кодировку.
+    ///
+    /// Кодировка берётся из BOM; иначе корректный UTF-8 читается как есть, затем проверяется
+    /// `<meta charset>`, а если объявления нет или оно не совпадает с байтами — кодировка
+    /// угадывается по содержимому (старые отчёты приходят в Windows-1251).
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let (html, encoding) = encoding::decode(bytes);
+        Self {
+            html,
+            encoding: Some(encoding),
+            sheets: None,
+        }
+    }
+
+    /// Декодирует HTML-отчёт в явно указанной кодировке (`windows-1251`, `koi8-r` и т. п.).
+    ///
+    /// # Errors
+    ///
+    /// Возвращает [`ReportError::UnknownEncoding`], если метка кодировки не распознана.
+    pub fn from_bytes_with_encoding(bytes: &[u8], label: &str) -> Result<Self, ReportError> {
+        let (html, encoding) = encoding::decode_with_label(bytes, label).ok_or_else(|| {
+            ReportError::UnknownEncoding {
+                label: label.to_string(),
+            }
+        })?;
+        Ok(Self {
+            html,
+            encoding: Some(encoding),
+            sheets: None,
+        })
+    }
+
+    /// Читает все HTML-отчёты из ZIP-архива в порядке имён записей.
+    ///
+    /// Вложенные архивы и `.eml`-сообщения разбираются рекурсивно, прочие файлы пропускаются.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает [`ReportError::Archive`], если архив повреждён, запись больше 64 Мбайт
+    /// или архивы вложены глубже восьми уровней, либо [`ReportError::Io`].
+    pub fn from_zip<R: Read + Seek>(reader: R) -> Result<Vec<Self>, ReportError> {
+        sources::read_zip(reader)
+    }
+
+    /// Читает HTML-отчёты из вложений почтового сообщения (`.eml`, MIME).
+    ///
+    /// Вложения декодируются из base64/quoted-printable; вложенные архивы и пересланные
+    /// сообщения тоже учитываются.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает [`ReportError::Mail`], если данные не являются сообщением, или ошибку
+    /// чтения вложенного архива.
+    pub fn from_eml(bytes: &[u8]) -> Result<Vec<Self>, ReportError> {
+        sources::read_eml(bytes)
+    }
+
+    /// Читает отчёт, выгруженный брокером в XLSX.
+    ///
+    /// Таблицы листов приводятся к тем же сеткам ([`TableGrid`]), что и таблицы HTML-отчёта,
+    /// поэтому дальше отчёт разбирается теми же парсерами и даёт тот же [`crate::Report`].
+    /// Исходного HTML у такого отчёта нет: [`RawReport::html`] возвращает `None`.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает [`ReportError::Workbook`], если книгу не удалось прочитать или какая-либо
+    /// её часть в распакованном виде больше 64 Мбайт.
+    pub fn from_xlsx<R: Read + Seek>(reader: R) -> Result<Self, ReportError> {
+        xlsx::read_xlsx(reader)
+    }
+
+    pub(crate) const fn from_sheets(sheets: SheetDocument) -> Self {
+        Self {
+            html: String::new(),
+            encoding: None,
+            sheets: Some(sheets),
+        }
+    }
+
+    /// Возвращает кодировку исходных байтов; `None`, если отчёт создан из готовой строки.
+    #[must_use]
+    pub const fn encoding(&self) -> Option<TextEncoding> {
+        self.encoding
+    }
+
+    /// Возвращает полный HTML отчёта; `None` для отчёта, прочитанного из XLSX.
+    #[must_use]
+    pub fn html(&self) -> Option<&str> {
+        self.sheets.is_none().then_some(self.html.as_str())
     }
 
     /// Создаёт отчёт из готовой HTML-строки.
@@ -30,6 +149,80 @@ impl RawReport {
     pub fn from_html(s: &str) -> Self {
         Self {
             html: s.to_string(),
+            encoding: None,
+            sheets: None,
+        }
+    }
+
+    /// Делит сводный отчёт по нескольким договорам на отдельные отчёты.
+    ///
+    /// Граница договора — заголовок `<h3>`, текст которого содержит период отчёта
+    /// («за период с … по …, дата создания …»); прочие заголовки границей не считаются.
+    /// Учитываются заголовки с тем же родителем, что и первый из них. Каждая часть получает
+    /// `<head>` документа, узлы перед первым заголовком и узлы от своего заголовка до
+    /// следующего. Отчёт с одним таким заголовком (или без них), как и отчёт из XLSX,
+    /// возвращается целиком.
+    #[must_use]
+    pub fn split_accounts(&self) -> Vec<Self> {
+        if self.sheets.is_some() {
+            return vec![self.clone()];
+        }
+        let doc = Html::parse_document(&self.html);
+        let mut headings = doc
+            .select(&H3_SELECTOR)
+            .filter(|heading| PERIOD_RE.is_match(&collect_text(*heading)));
+        let Some(first) = headings.next() else {
+            return vec![self.clone()];
+        };
+        let Some(parent) = first.parent() else {
+            return vec![self.clone()];
+        };
+        let boundaries: Vec<_> = std::iter::once(first)
+            .chain(headings.filter(|heading| heading.parent() == Some(parent)))
+            .map(|heading| heading.id())
+            .collect();
+        if boundaries.len() < 2 {
+            return vec![self.clone()];
+        }
+
+        let head = doc
+            .select(&HEAD_SELECTOR)
+            .next()
+            .map(|head| head.html())
+            .unwrap_or_default();
+        let mut prologue = String::new();
+        let mut parts: Vec<String> = Vec::new();
+        for node in parent.children() {
+            if boundaries.contains(&node.id()) {
+                parts.push(String::new());
+            }
+            let target = parts.last_mut().unwrap_or(&mut prologue);
+            if let Some(element) = ElementRef::wrap(node) {
+                target.push_str(&element.html());
+            } else if let Some(text) = node.value().as_text() {
+                push_escaped(target, text);
+            }
+        }
+
+        parts
+            .into_iter()
+            .map(|part| Self {
+                html: format!("<html>{head}<body>{prologue}{part}</body></html>"),
+                encoding: self.encoding,
+                sheets: None,
+            })
+            .collect()
+    }
+}
+
+/// Дописывает текстовый узел в HTML, экранируя служебные символы.
+fn push_escaped(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            ch => out.push(ch),
         }
     }
 }
@@ -45,19 +238,128 @@ impl FromStr for RawReport {
 /// Разобранный DOM отчёта с удобными методами поиска таблиц.
 #[derive(Debug, Clone)]
 pub struct DomReport {
-    pub(crate) doc: Html,
+    source: DomSource,
+}
+
+/// Документ, из которого берутся шапка и таблицы отчёта.
+#[derive(Debug, Clone)]
+enum DomSource {
+    Html(Html),
+    Sheets(SheetDocument),
 }
 
 impl DomReport {
-    /// Парсит DOM из исходного HTML.
+    /// Парсит DOM из исходного HTML; для отчёта из XLSX берёт уже разобранные листы.
     ///
     /// # Errors
     ///
     /// Сейчас парсер DOM не возвращает ошибки, но сигнатура оставлена в виде `Result`
     /// для согласованности API верхнего уровня.
     pub fn parse(raw: &RawReport) -> Result<Self, ReportError> {
-        Ok(Self {
-            doc: Html::parse_document(&raw.html),
-        })
+        let source = raw.sheets.clone().map_or_else(
+            || DomSource::Html(Html::parse_document(&raw.html)),
+            DomSource::Sheets,
+        );
+        Ok(Self { source })
+    }
+
+    /// Находит таблицу по фразам заголовка и возвращает её сетку.
+    ///
+    /// Сокращение для [`DomReport::find_table`] с теми же правилами сопоставления.
+    /// Позволяет разбирать таблицы, которые крейт пока не поддерживает.
+    #[must_use]
+    pub fn table_grid(&self, required_headers: &[&str]) -> Option<TableGrid> {
+        self.find_table(required_headers)
+            .map(ReportTable::into_grid)
     }
+
+    /// Возвращает все таблицы документа в порядке следования с заголовками разделов.
+    ///
+    /// Заголовок — подпись `caption` таблицы или текст ближайшего предшествующего абзаца
+    /// (`p`, `h1`–`h6`), ещё не отданного другой таблице; в отчёте из XLSX — текстовый блок
+    /// листа перед таблицей. Вложенные таблицы не выделяются. Сетка каждой таблицы
+    /// строится по мере обхода итератора.
+    pub fn tables(&self) -> impl Iterator<Item = ReportTable> + '_ {
+        let (html, sheets) = match &self.source {
+            DomSource::Html(doc) => (Some(html_tables(doc)), None),
+            DomSource::Sheets(sheets) => (None, Some(sheets.tables())),
+        };
+        html.into_iter()
+            .flatten()
+            .chain(sheets.into_iter().flatten())
+    }
+
+    /// Находит первую таблицу, в заголовке которой встречаются все фразы.
+    ///
+    /// Каждая фраза ищется как подстрока в ячейках строк заголовка таблицы — строк с классом
+    /// `table-header` или первой строки, если класс не проставлен (см. [`TableGrid`]).
+    /// Таблицы перебираются по порядку, и сетки строятся только до первого совпадения.
+    #[must_use]
+    pub fn find_table(&self, phrases: &[&str]) -> Option<ReportTable> {
+        self.tables().find(|table| table.has_headers(phrases))
+    }
+
+    /// Текст шапки отчёта (первого заголовка `h3`).
+    pub(crate) fn heading_text(&self) -> Option<String> {
+        match &self.source {
+            DomSource::Html(doc) => doc.select(&H3_SELECTOR).next().map(collect_text),
+            DomSource::Sheets(sheets) => sheets.heading(),
+        }
+    }
+
+    /// Тексты абзацев `p` без нормализации пробелов.
+    pub(crate) fn paragraphs(&self) -> impl Iterator<Item = String> + '_ {
+        let (html, sheets) = match &self.source {
+            DomSource::Html(doc) => (
+                Some(doc.select(&P_SELECTOR).map(|p| p.text().collect())),
+                None,
+            ),
+            DomSource::Sheets(sheets) => (None, Some(sheets.paragraphs())),
+        };
+        html.into_iter()
+            .flatten()
+            .chain(sheets.into_iter().flatten())
+    }
+
+    /// Сетка таблицы «Оценка активов»: в HTML она помечена классом `RatingAssets`,
+    /// в XLSX — первая таблица с её заголовками.
+    pub(crate) fn asset_valuation_grid(&self) -> Option<TableGrid> {
+        match &self.source {
+            DomSource::Html(doc) => doc
+                .select(&RATING_SELECTOR)
+                .next()
+                .map(TableGrid::from_element),
+            DomSource::Sheets(_) => self.table_grid(&ASSET_VALUATION_HEADERS),
+        }
+    }
+}
+
+/// Таблицы HTML-документа верхнего уровня с заголовками разделов.
+fn html_tables(doc: &Html) -> impl Iterator<Item = ReportTable> + '_ {
+    let mut pending_heading: Option<String> = None;
+
+    doc.select(&BLOCK_SELECTOR)
+        .filter(|element| {
+            !element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|parent| parent.value().name() == "table")
+        })
+        .filter_map(move |element| {
+            if element.value().name() != "table" {
+                let text = collect_text(element);
+                if !text.is_empty() {
+                    pending_heading = Some(text);
+                }
+                return None;
+            }
+
+            let caption = element
+                .select(&CAPTION_SELECTOR)
+                .next()
+                .map(collect_text)
+                .filter(|text| !text.is_empty());
+            let heading = caption.or_else(|| pending_heading.take());
+            Some(ReportTable::new(heading, TableGrid::from_element(element)))
+        })
 }
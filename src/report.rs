@@ -1,14 +1,23 @@
 //! Обёртка над парсерами для построения итогового отчёта.
 
 use crate::diagnostics::ParseWarning;
+use crate::encoding::TextEncoding;
 use crate::error::ReportError;
 use crate::parse_config::{ParseConfig, ParseMode, ReportSection, SectionSet};
+use crate::parser::{attach_acquisition_costs, classify_positions, mark_blocked_positions};
 use crate::raw::{DomReport, RawReport};
 use crate::types::{
-    AssetValuation, CashFlowRow, CashFlowSummary, IisContribution, IisContributionsTable,
-    Portfolio, PortfolioMarket, ReportMetadata, SecurityPosition,
+    AssetValuation, BlockedHolding, BlockedHoldingsTable, CashFlowKind, CashFlowRow,
+    CashFlowSummary, CashOperation, CashOperationsTable, DerivativesSection, FxTrade,
+    FxTradesTable, IisContribution, IisContributionsTable, IncomeEvent, IncomeEventsTable,
+    MarginPosition, MarginSection, Money, OtcOperation, OtcOperationsTable, PendingSettlement,
+    PendingSettlements, Portfolio, PortfolioMarket, RepoDeal, RepoDealsTable, ReportMetadata,
+    SecurityInfo, SecurityMovement, SecurityMovementsTable, SecurityPosition, SecurityReference,
+    SettlementSource, TaxSummary, Trade, TradesTable, VariationMarginRow,
 };
 
+use std::collections::{BTreeMap, BTreeSet};
+
 /// Итоговая модель одного отчёта.
 #[derive(Debug, Clone)]
 pub struct Report {
@@ -22,6 +31,32 @@ pub struct Report {
     pub(crate) portfolio: Option<Portfolio>,
     /// Таблица пополнений ИИС.
     pub(crate) iis_contributions: Option<IisContributionsTable>,
+    /// Сделки купли/продажи ценных бумаг.
+    pub(crate) trades: Option<TradesTable>,
+    /// Движение денежных средств с отдельными операциями.
+    pub(crate) cash_operations: Option<CashOperationsTable>,
+    /// Движение ценных бумаг.
+    pub(crate) security_movements: Option<SecurityMovementsTable>,
+    /// Сделки с валютными инструментами.
+    pub(crate) fx_trades: Option<FxTradesTable>,
+    /// Справочник ценных бумаг.
+    pub(crate) security_reference: Option<SecurityReference>,
+    /// Выплаты доходов по ценным бумагам.
+    pub(crate) income_events: Option<IncomeEventsTable>,
+    /// Налоговый блок.
+    pub(crate) tax_summary: Option<TaxSummary>,
+    /// Сделки РЕПО.
+    pub(crate) repo_deals: Option<RepoDealsTable>,
+    /// Срочный рынок.
+    pub(crate) derivatives: Option<DerivativesSection>,
+    /// Незавершённые расчёты.
+    pub(crate) pending_settlements: Option<PendingSettlements>,
+    /// Необеспеченные позиции.
+    pub(crate) margin: Option<MarginSection>,
+    /// Заблокированные ценные бумаги.
+    pub(crate) blocked_holdings: Option<BlockedHoldingsTable>,
+    /// Внебиржевые сделки и переводы ЦБ.
+    pub(crate) otc_operations: Option<OtcOperationsTable>,
 }
 
 impl Report {
@@ -55,6 +90,179 @@ impl Report {
         self.iis_contributions.as_ref()
     }
 
+    /// Возвращает таблицу сделок купли/продажи, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn trades(&self) -> Option<&TradesTable> {
+        self.trades.as_ref()
+    }
+
+    /// Возвращает таблицу операций с денежными средствами, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn cash_operations(&self) -> Option<&CashOperationsTable> {
+        self.cash_operations.as_ref()
+    }
+
+    /// Возвращает таблицу движения ценных бумаг, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn security_movements(&self) -> Option<&SecurityMovementsTable> {
+        self.security_movements.as_ref()
+    }
+
+    /// Возвращает таблицу валютных сделок, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn fx_trades(&self) -> Option<&FxTradesTable> {
+        self.fx_trades.as_ref()
+    }
+
+    /// Возвращает справочник ценных бумаг, если он был запрошен и найден.
+    #[must_use]
+    pub const fn security_reference(&self) -> Option<&SecurityReference> {
+        self.security_reference.as_ref()
+    }
+
+    /// Возвращает запись справочника ценных бумаг по ISIN.
+    #[must_use]
+    pub fn security_info(&self, isin: &str) -> Option<&SecurityInfo> {
+        self.security_reference
+            .as_ref()
+            .and_then(|reference| reference.get(isin))
+    }
+
+    /// Возвращает ISIN бумаги по коду в торговой системе из справочника ценных бумаг.
+    #[must_use]
+    pub fn isin_by_code(&self, code: &str) -> Option<&str> {
+        self.security_reference
+            .as_ref()
+            .and_then(|reference| reference.isin_by_code(code))
+    }
+
+    /// Возвращает таблицу выплат доходов, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn income_events(&self) -> Option<&IncomeEventsTable> {
+        self.income_events.as_ref()
+    }
+
+    /// Возвращает налоговый блок, если он был запрошен и найден.
+    #[must_use]
+    pub const fn tax_summary(&self) -> Option<&TaxSummary> {
+        self.tax_summary.as_ref()
+    }
+
+    /// Возвращает таблицу сделок РЕПО, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn repo_deals(&self) -> Option<&RepoDealsTable> {
+        self.repo_deals.as_ref()
+    }
+
+    /// Возвращает чистый доход по сделкам РЕПО за период отчёта в разрезе валют.
+    #[must_use]
+    pub fn net_repo_income(&self) -> BTreeMap<String, Money> {
+        self.repo_deals
+            .as_ref()
+            .map(RepoDealsTable::income_by_currency)
+            .unwrap_or_default()
+    }
+
+    /// Возвращает секцию срочного рынка, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn derivatives(&self) -> Option<&DerivativesSection> {
+        self.derivatives.as_ref()
+    }
+
+    /// Возвращает незавершённые расчёты, если они были запрошены и найдены.
+    #[must_use]
+    pub const fn pending_settlements(&self) -> Option<&PendingSettlements> {
+        self.pending_settlements.as_ref()
+    }
+
+    /// Возвращает прогноз остатка денежных средств по валютам: исходящий остаток
+    /// из сводки движения ДС плюс незавершённые расчёты.
+    ///
+    /// Плановые движения таблицы «Денежные средства» уже включают расчёты по валютным
+    /// сделкам, поэтому для валюты с такими движениями прогноз совпадает с «Плановым
+    /// исходящим остатком» брокера, а требования и обязательства валютного рынка по ней
+    /// не добавляются. Они учитываются только для валют, по которым брокер плановых
+    /// движений не показал. Незагруженные секции считаются пустыми.
+    #[must_use]
+    pub fn projected_cash_by_currency(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in self
+            .cash_flow_rows()
+            .filter(|row| row.kind == CashFlowKind::ClosingBalance)
+        {
+            *map.entry(row.currency.clone()).or_insert(Money::ZERO) += row.amount;
+        }
+        let broker_currencies: BTreeSet<&str> = self
+            .pending_settlement_rows()
+            .filter(|row| row.source == SettlementSource::BrokerCash)
+            .map(|row| row.currency.as_str())
+            .collect();
+        for row in self.pending_settlement_rows().filter(|row| {
+            row.source == SettlementSource::BrokerCash
+                || !broker_currencies.contains(row.currency.as_str())
+        }) {
+            *map.entry(row.currency.clone()).or_insert(Money::ZERO) += row.net();
+        }
+        map
+    }
+
+    /// Возвращает секцию необеспеченных позиций, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn margin(&self) -> Option<&MarginSection> {
+        self.margin.as_ref()
+    }
+
+    /// Возвращает `true`, если в отчёте есть заёмные средства или короткие позиции.
+    #[must_use]
+    pub fn uses_leverage(&self) -> bool {
+        self.margin_rows().next().is_some()
+            || self
+                .portfolio
+                .as_ref()
+                .is_some_and(Portfolio::has_short_positions)
+    }
+
+    /// Возвращает таблицу заблокированных бумаг, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn blocked_holdings(&self) -> Option<&BlockedHoldingsTable> {
+        self.blocked_holdings.as_ref()
+    }
+
+    /// Суммирует стоимость бумаг без НКД на конец периода по валютам цены.
+    ///
+    /// При `include_blocked = false` из позиций вычитается заблокированная доля
+    /// пропорционально количеству; при `true` к портфелю добавляются заблокированные
+    /// бумаги, отсутствующие в нём.
+    #[must_use]
+    pub fn valuation_by_currency(&self, include_blocked: bool) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for position in self.positions() {
+            let value = if include_blocked {
+                position.value_end_no_ai
+            } else {
+                position.unblocked_value_end_no_ai()
+            };
+            *map.entry(position.price_currency.clone())
+                .or_insert(Money::ZERO) += value;
+        }
+        if include_blocked {
+            for holding in self.blocked_rows().filter(|holding| {
+                !self
+                    .positions()
+                    .any(|position| position.isin == holding.isin)
+            }) {
+                *map.entry(holding.currency.clone()).or_insert(Money::ZERO) += holding.valuation;
+            }
+        }
+        map
+    }
+
+    /// Возвращает таблицу внебиржевых операций, если она была запрошена и найдена.
+    #[must_use]
+    pub const fn otc_operations(&self) -> Option<&OtcOperationsTable> {
+        self.otc_operations.as_ref()
+    }
+
     /// Возвращает копию отчёта с заменённой таблицей оценки активов.
     #[must_use]
     pub fn with_asset_valuation(mut self, asset_valuation: Option<AssetValuation>) -> Self {
@@ -86,6 +294,106 @@ impl Report {
         self
     }
 
+    /// Возвращает копию отчёта с заменённой таблицей сделок.
+    #[must_use]
+    pub fn with_trades(mut self, trades: Option<TradesTable>) -> Self {
+        self.trades = trades;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей операций с денежными средствами.
+    #[must_use]
+    pub fn with_cash_operations(mut self, cash_operations: Option<CashOperationsTable>) -> Self {
+        self.cash_operations = cash_operations;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей движения ценных бумаг.
+    #[must_use]
+    pub fn with_security_movements(
+        mut self,
+        security_movements: Option<SecurityMovementsTable>,
+    ) -> Self {
+        self.security_movements = security_movements;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей валютных сделок.
+    #[must_use]
+    pub fn with_fx_trades(mut self, fx_trades: Option<FxTradesTable>) -> Self {
+        self.fx_trades = fx_trades;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённым справочником ценных бумаг.
+    #[must_use]
+    pub fn with_security_reference(
+        mut self,
+        security_reference: Option<SecurityReference>,
+    ) -> Self {
+        self.security_reference = security_reference;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей выплат доходов.
+    #[must_use]
+    pub fn with_income_events(mut self, income_events: Option<IncomeEventsTable>) -> Self {
+        self.income_events = income_events;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённым налоговым блоком.
+    #[must_use]
+    pub fn with_tax_summary(mut self, tax_summary: Option<TaxSummary>) -> Self {
+        self.tax_summary = tax_summary;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей сделок РЕПО.
+    #[must_use]
+    pub fn with_repo_deals(mut self, repo_deals: Option<RepoDealsTable>) -> Self {
+        self.repo_deals = repo_deals;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой секцией срочного рынка.
+    #[must_use]
+    pub fn with_derivatives(mut self, derivatives: Option<DerivativesSection>) -> Self {
+        self.derivatives = derivatives;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменёнными незавершёнными расчётами.
+    #[must_use]
+    pub fn with_pending_settlements(
+        mut self,
+        pending_settlements: Option<PendingSettlements>,
+    ) -> Self {
+        self.pending_settlements = pending_settlements;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой секцией необеспеченных позиций.
+    #[must_use]
+    pub fn with_margin(mut self, margin: Option<MarginSection>) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей заблокированных бумаг.
+    #[must_use]
+    pub fn with_blocked_holdings(mut self, blocked_holdings: Option<BlockedHoldingsTable>) -> Self {
+        self.blocked_holdings = blocked_holdings;
+        self
+    }
+
+    /// Возвращает копию отчёта с заменённой таблицей внебиржевых операций.
+    #[must_use]
+    pub fn with_otc_operations(mut self, otc_operations: Option<OtcOperationsTable>) -> Self {
+        self.otc_operations = otc_operations;
+        self
+    }
+
     /// Возвращает итератор по строкам движения денежных средств без дополнительных аллокаций.
     #[inline]
     pub fn cash_flow_rows(&self) -> impl Iterator<Item = &CashFlowRow> {
@@ -106,6 +414,15 @@ impl Report {
         self.portfolio.iter().flat_map(Portfolio::iter_positions)
     }
 
+    /// Возвращает итератор по позициям портфеля вместе с записями справочника ценных бумаг.
+    #[inline]
+    pub fn positions_with_info(
+        &self,
+    ) -> impl Iterator<Item = (&SecurityPosition, Option<&SecurityInfo>)> {
+        self.positions()
+            .map(|position| (position, self.security_info(&position.isin)))
+    }
+
     /// Возвращает итератор по строкам таблицы пополнений ИИС без дополнительных аллокаций.
     #[inline]
     pub fn iis_rows(&self) -> impl Iterator<Item = &IisContribution> {
@@ -114,6 +431,86 @@ impl Report {
             .flat_map(IisContributionsTable::iter_rows)
     }
 
+    /// Возвращает итератор по сделкам купли/продажи без дополнительных аллокаций.
+    #[inline]
+    pub fn trade_rows(&self) -> impl Iterator<Item = &Trade> {
+        self.trades.iter().flat_map(TradesTable::iter_rows)
+    }
+
+    /// Возвращает итератор по операциям с денежными средствами без дополнительных аллокаций.
+    #[inline]
+    pub fn cash_operation_rows(&self) -> impl Iterator<Item = &CashOperation> {
+        self.cash_operations
+            .iter()
+            .flat_map(CashOperationsTable::iter_rows)
+    }
+
+    /// Возвращает итератор по операциям движения ценных бумаг без дополнительных аллокаций.
+    #[inline]
+    pub fn security_movement_rows(&self) -> impl Iterator<Item = &SecurityMovement> {
+        self.security_movements
+            .iter()
+            .flat_map(SecurityMovementsTable::iter_rows)
+    }
+
+    /// Возвращает итератор по валютным сделкам без дополнительных аллокаций.
+    #[inline]
+    pub fn fx_trade_rows(&self) -> impl Iterator<Item = &FxTrade> {
+        self.fx_trades.iter().flat_map(FxTradesTable::iter_rows)
+    }
+
+    /// Возвращает итератор по выплатам доходов без дополнительных аллокаций.
+    #[inline]
+    pub fn income_rows(&self) -> impl Iterator<Item = &IncomeEvent> {
+        self.income_events
+            .iter()
+            .flat_map(IncomeEventsTable::iter_rows)
+    }
+
+    /// Возвращает итератор по сделкам РЕПО без дополнительных аллокаций.
+    #[inline]
+    pub fn repo_rows(&self) -> impl Iterator<Item = &RepoDeal> {
+        self.repo_deals.iter().flat_map(RepoDealsTable::iter_rows)
+    }
+
+    /// Возвращает итератор по строкам вариационной маржи без дополнительных аллокаций.
+    #[inline]
+    pub fn variation_margin_rows(&self) -> impl Iterator<Item = &VariationMarginRow> {
+        self.derivatives
+            .iter()
+            .flat_map(|section| section.variation_margin.iter())
+    }
+
+    /// Возвращает итератор по незавершённым расчётам без дополнительных аллокаций.
+    #[inline]
+    pub fn pending_settlement_rows(&self) -> impl Iterator<Item = &PendingSettlement> {
+        self.pending_settlements
+            .iter()
+            .flat_map(PendingSettlements::iter_rows)
+    }
+
+    /// Возвращает итератор по необеспеченным позициям без дополнительных аллокаций.
+    #[inline]
+    pub fn margin_rows(&self) -> impl Iterator<Item = &MarginPosition> {
+        self.margin.iter().flat_map(MarginSection::iter_rows)
+    }
+
+    /// Возвращает итератор по заблокированным бумагам без дополнительных аллокаций.
+    #[inline]
+    pub fn blocked_rows(&self) -> impl Iterator<Item = &BlockedHolding> {
+        self.blocked_holdings
+            .iter()
+            .flat_map(BlockedHoldingsTable::iter_rows)
+    }
+
+    /// Возвращает итератор по внебиржевым операциям без дополнительных аллокаций.
+    #[inline]
+    pub fn otc_rows(&self) -> impl Iterator<Item = &OtcOperation> {
+        self.otc_operations
+            .iter()
+            .flat_map(OtcOperationsTable::iter_rows)
+    }
+
     /// Парсит один HTML-отчёт в мягком режиме, загружая все секции.
     ///
     /// # Errors
@@ -134,6 +531,31 @@ impl Report {
         Self::parse_with_config(raw, ParseConfig::strict())
     }
 
+    /// Парсит сводный HTML-отчёт и возвращает отдельный [`Report`] на каждый договор.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если не удалось распарсить хотя бы одну из частей.
+    #[inline]
+    pub fn parse_all(raw: &RawReport) -> Result<Vec<Self>, ReportError> {
+        Self::parse_all_with_config(raw, ParseConfig::default())
+    }
+
+    /// Парсит сводный HTML-отчёт с явной конфигурацией, по одному [`Report`] на договор.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если не удалось распарсить хотя бы одну из частей.
+    pub fn parse_all_with_config(
+        raw: &RawReport,
+        config: ParseConfig,
+    ) -> Result<Vec<Self>, ReportError> {
+        raw.split_accounts()
+            .iter()
+            .map(|part| Self::parse_with_config(part, config))
+            .collect()
+    }
+
     /// Парсит отчёт с явной конфигурацией секций и режима.
     ///
     /// # Errors
@@ -142,11 +564,14 @@ impl Report {
     /// преобразуется в `None`, а в строгом режиме возвращается как ошибка.
     pub fn parse_with_config(raw: &RawReport, config: ParseConfig) -> Result<Self, ReportError> {
         let mut ignored_warnings = Vec::new();
-        Self::parse_with_warnings(raw, config, &mut ignored_warnings)
+        Self::parse_with_warnings(raw, config, false, &mut ignored_warnings)
     }
 
     /// Парсит отчёт и возвращает предупреждения мягкого режима.
     ///
+    /// Только в этом режиме документ дополнительно проверяется на таблицы, не относящиеся
+    /// ни к одной известной секции ([`ParseWarning::UnrecognizedTable`]).
+    ///
     /// # Errors
     ///
     /// Возвращает ошибку, если встретились критические проблемы для выбранного режима.
@@ -155,17 +580,27 @@ impl Report {
         config: ParseConfig,
     ) -> Result<(Self, Vec<ParseWarning>), ReportError> {
         let mut warnings = Vec::new();
-        let report = Self::parse_with_warnings(raw, config, &mut warnings)?;
+        let report = Self::parse_with_warnings(raw, config, true, &mut warnings)?;
         Ok((report, warnings))
     }
 
+    // Линейный перечень секций: дробление на части не упрощает чтение.
+    #[allow(clippy::too_many_lines)]
     fn parse_with_warnings(
         raw: &RawReport,
         config: ParseConfig,
+        list_unrecognized: bool,
         warnings: &mut Vec<ParseWarning>,
     ) -> Result<Self, ReportError> {
         let dom = DomReport::parse(raw)?;
         let meta = dom.meta()?;
+        if let Some(encoding) = raw.encoding().filter(TextEncoding::is_transcoded) {
+            warnings.push(ParseWarning::Transcoded { encoding });
+        }
+        // Инвентаризация обходит все таблицы документа: нужна только вызывающим диагностику.
+        if list_unrecognized {
+            dom.report_unrecognized_tables(warnings);
+        }
 
         let asset_valuation = parse_optional(
             config,
@@ -179,15 +614,99 @@ impl Report {
             warnings,
             |warnings| dom.parse_cash_flow_summary_with_mode(config.mode, warnings),
         )?;
-        let portfolio = parse_optional(config, ReportSection::Portfolio, warnings, |warnings| {
-            dom.parse_portfolio_with_mode(config.mode, warnings)
-        })?;
+        // Справочник, блокировки и внебиржевые операции разбираются один раз: они нужны
+        // портфелю, сделкам и движению ЦБ и тогда, когда сами секции не запрошены.
+        let security_reference = parse_dependency(
+            config,
+            ReportSection::SecurityReference,
+            config.loads(ReportSection::Portfolio) || config.loads(ReportSection::Trades),
+            warnings,
+            |warnings| dom.parse_security_reference_with_mode(config.mode, warnings),
+        )?;
+        let blocked_holdings = parse_dependency(
+            config,
+            ReportSection::BlockedHoldings,
+            config.loads(ReportSection::Portfolio),
+            warnings,
+            |warnings| dom.parse_blocked_holdings_with_mode(config.mode, warnings),
+        )?;
+        let otc_operations = parse_dependency(
+            config,
+            ReportSection::OtcOperations,
+            config.loads(ReportSection::SecurityMovements),
+            warnings,
+            |warnings| dom.parse_otc_operations_with_mode(config.mode, warnings),
+        )?;
+
+        let mut portfolio =
+            parse_optional(config, ReportSection::Portfolio, warnings, |warnings| {
+                dom.parse_portfolio_with_mode(config.mode, warnings)
+            })?;
         let iis_contributions = parse_optional(
             config,
             ReportSection::IisContributions,
             warnings,
             |warnings| dom.parse_iis_contributions_with_mode(config.mode, warnings),
         )?;
+        let trades = parse_optional(config, ReportSection::Trades, warnings, |warnings| {
+            dom.parse_trades_with_mode(config.mode, security_reference.as_ref(), warnings)
+        })?;
+        let cash_operations = parse_optional(
+            config,
+            ReportSection::CashOperations,
+            warnings,
+            |warnings| dom.parse_cash_operations_with_mode(config.mode, warnings),
+        )?;
+        let mut security_movements = parse_optional(
+            config,
+            ReportSection::SecurityMovements,
+            warnings,
+            |warnings| dom.parse_security_movements_with_mode(config.mode, warnings),
+        )?;
+        let fx_trades = parse_optional(config, ReportSection::FxTrades, warnings, |warnings| {
+            dom.parse_fx_trades_with_mode(config.mode, warnings)
+        })?;
+        let income_events =
+            parse_optional(config, ReportSection::IncomeEvents, warnings, |warnings| {
+                dom.parse_income_events_with_mode(config.mode, warnings)
+            })?;
+        let tax_summary =
+            parse_optional(config, ReportSection::TaxSummary, warnings, |warnings| {
+                dom.parse_tax_summary_with_mode(config.mode, warnings)
+            })?;
+        let repo_deals = parse_optional(config, ReportSection::RepoDeals, warnings, |warnings| {
+            dom.parse_repo_deals_with_mode(config.mode, warnings)
+        })?;
+        let derivatives =
+            parse_optional(config, ReportSection::Derivatives, warnings, |warnings| {
+                dom.parse_derivatives_with_mode(config.mode, warnings)
+            })?;
+        let pending_settlements = parse_optional(
+            config,
+            ReportSection::PendingSettlements,
+            warnings,
+            |warnings| dom.parse_pending_settlements_with_mode(config.mode, warnings),
+        )?;
+        let margin = parse_optional(config, ReportSection::Margin, warnings, |warnings| {
+            dom.parse_margin_with_mode(config.mode, warnings)
+        })?;
+
+        if let Some(portfolio) = portfolio.as_mut() {
+            classify_positions(portfolio, security_reference.as_ref(), warnings);
+            if let Some(blocked) = blocked_holdings.as_ref() {
+                mark_blocked_positions(portfolio, blocked);
+            }
+        }
+        if let (Some(movements), Some(otc)) = (security_movements.as_mut(), otc_operations.as_ref())
+        {
+            attach_acquisition_costs(movements, otc);
+        }
+        let requested = |section| config.loads(section);
+        let security_reference =
+            security_reference.filter(|_| requested(ReportSection::SecurityReference));
+        let blocked_holdings =
+            blocked_holdings.filter(|_| requested(ReportSection::BlockedHoldings));
+        let otc_operations = otc_operations.filter(|_| requested(ReportSection::OtcOperations));
 
         Ok(Self {
             meta,
@@ -195,6 +714,19 @@ impl Report {
             cash_flow_summary,
             portfolio,
             iis_contributions,
+            trades,
+            cash_operations,
+            security_movements,
+            fx_trades,
+            security_reference,
+            income_events,
+            tax_summary,
+            repo_deals,
+            derivatives,
+            pending_settlements,
+            margin,
+            blocked_holdings,
+            otc_operations,
         })
     }
 }
@@ -300,7 +832,8 @@ impl<'a> ReportBuilder<'a> {
     }
 }
 
-/// Вызывает парсер секции, возвращая `None` в мягком режиме при отсутствии таблицы.
+/// Вызывает парсер секции, возвращая `None` в мягком режиме при отсутствии таблицы или
+/// обязательного столбца.
 fn parse_optional<T, F>(
     config: ParseConfig,
     section: ReportSection,
@@ -320,6 +853,52 @@ where
             warnings.push(ParseWarning::MissingTable { section, table });
             Ok(None)
         }
+        Err(ReportError::MissingColumn { table, column }) if !config.mode.is_strict() => {
+            warnings.push(ParseWarning::SkippedSection {
+                section,
+                table,
+                column,
+            });
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Разбирает вспомогательную секцию: запрошенную — как [`parse_optional`], а незапрошенную —
+/// только если она нужна другим секциям (`needed`).
+///
+/// Отсутствие незапрошенной таблицы не считается проблемой, а ошибка её разбора в мягком
+/// режиме становится предупреждением [`ParseWarning::DependencyFailed`].
+fn parse_dependency<T, F>(
+    config: ParseConfig,
+    section: ReportSection,
+    needed: bool,
+    warnings: &mut Vec<ParseWarning>,
+    loader: F,
+) -> Result<Option<T>, ReportError>
+where
+    F: FnOnce(&mut Vec<ParseWarning>) -> Result<T, ReportError>,
+{
+    if config.loads(section) {
+        return parse_optional(config, section, warnings, loader);
+    }
+    if !needed {
+        return Ok(None);
+    }
+
+    // Диагностика столбцов незапрошенной таблицы не нужна вызывающему коду.
+    let mut ignored_warnings = Vec::new();
+    match loader(&mut ignored_warnings) {
+        Ok(value) => Ok(Some(value)),
+        Err(ReportError::TableNotFound { .. }) => Ok(None),
+        Err(err) if !config.mode.is_strict() => {
+            warnings.push(ParseWarning::DependencyFailed {
+                section,
+                message: err.to_string(),
+            });
+            Ok(None)
+        }
         Err(err) => Err(err),
     }
 }
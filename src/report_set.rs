@@ -1,16 +1,21 @@
 //! Набор отчётов и функции их агрегации.
 
 use crate::error::ReportError;
+use crate::loader::{LoadOptions, LoadOutcome};
 use crate::parse_config::ParseConfig;
-use crate::raw::RawReport;
 use crate::report::{Report, ReportBuilder};
+use crate::sources::{self, SourceKind};
 use crate::types::{
-    AccountId, CashFlowKind, CashFlowRow, CashFlowSummary, MergedPosition, Money, SecurityPosition,
+    AccountId, BlockedHolding, CashFlowKind, CashFlowRow, CashFlowSummary, CashOperation, FxTrade,
+    IncomeEvent, IncomeTotals, MarginPosition, MergedPosition, Money, OtcOperation, Portfolio,
+    RepoDeal, ReportMetadata, SecurityMovement, SecurityPosition, TaxSummary, TaxTotals, Trade,
+    VariationMarginRow,
 };
+use chrono::Datelike;
 use rust_decimal::Decimal;
 use std::collections::BTreeMap;
-use std::fs::{self, DirEntry};
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 /// Набор отчётов с утилитами для агрегации.
 #[derive(Debug, Clone, Default)]
@@ -34,13 +39,13 @@ impl ReportSet {
 
     /// Возвращает количество отчётов.
     #[must_use]
-    pub fn len(&self) -> usize {
+    pub const fn len(&self) -> usize {
         self.reports.len()
     }
 
     /// Возвращает `true`, если набор пуст.
     #[must_use]
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.reports.is_empty()
     }
 
@@ -56,12 +61,72 @@ impl ReportSet {
         self.reports.iter().flat_map(Report::cash_flow_rows)
     }
 
+    /// Возвращает итератор по операциям с денежными средствами всех отчётов.
+    #[inline]
+    pub fn iter_cash_operations(&self) -> impl Iterator<Item = &CashOperation> {
+        self.reports.iter().flat_map(Report::cash_operation_rows)
+    }
+
+    /// Возвращает итератор по операциям движения ценных бумаг всех отчётов.
+    #[inline]
+    pub fn iter_security_movements(&self) -> impl Iterator<Item = &SecurityMovement> {
+        self.reports.iter().flat_map(Report::security_movement_rows)
+    }
+
+    /// Возвращает итератор по валютным сделкам всех отчётов.
+    #[inline]
+    pub fn iter_fx_trades(&self) -> impl Iterator<Item = &FxTrade> {
+        self.reports.iter().flat_map(Report::fx_trade_rows)
+    }
+
+    /// Возвращает итератор по выплатам доходов всех отчётов.
+    #[inline]
+    pub fn iter_income_events(&self) -> impl Iterator<Item = &IncomeEvent> {
+        self.reports.iter().flat_map(Report::income_rows)
+    }
+
+    /// Возвращает итератор по сделкам РЕПО всех отчётов.
+    #[inline]
+    pub fn iter_repo_deals(&self) -> impl Iterator<Item = &RepoDeal> {
+        self.reports.iter().flat_map(Report::repo_rows)
+    }
+
+    /// Возвращает итератор по строкам вариационной маржи всех отчётов.
+    #[inline]
+    pub fn iter_variation_margin(&self) -> impl Iterator<Item = &VariationMarginRow> {
+        self.reports.iter().flat_map(Report::variation_margin_rows)
+    }
+
+    /// Возвращает итератор по необеспеченным позициям всех отчётов.
+    #[inline]
+    pub fn iter_margin_positions(&self) -> impl Iterator<Item = &MarginPosition> {
+        self.reports.iter().flat_map(Report::margin_rows)
+    }
+
+    /// Возвращает итератор по заблокированным бумагам всех отчётов.
+    #[inline]
+    pub fn iter_blocked_holdings(&self) -> impl Iterator<Item = &BlockedHolding> {
+        self.reports.iter().flat_map(Report::blocked_rows)
+    }
+
+    /// Возвращает итератор по внебиржевым операциям всех отчётов.
+    #[inline]
+    pub fn iter_otc_operations(&self) -> impl Iterator<Item = &OtcOperation> {
+        self.reports.iter().flat_map(Report::otc_rows)
+    }
+
     /// Возвращает итератор по позициям портфеля всех отчётов.
     #[inline]
     pub fn iter_positions(&self) -> impl Iterator<Item = &SecurityPosition> {
         self.reports.iter().flat_map(Report::positions)
     }
 
+    /// Возвращает итератор по сделкам купли/продажи всех отчётов.
+    #[inline]
+    pub fn iter_trades(&self) -> impl Iterator<Item = &Trade> {
+        self.reports.iter().flat_map(Report::trade_rows)
+    }
+
     /// Загружает и парсит все HTML-файлы из каталога с полным набором таблиц.
     ///
     /// # Errors
@@ -95,7 +160,8 @@ impl ReportSet {
         Self::from_dir_with_config(dir, ParseConfig::strict())
     }
 
-    /// Загружает и парсит все HTML-файлы из каталога, позволяя настроить билдер.
+    /// Загружает и парсит все HTML- и XLSX-отчёты, ZIP-архивы и `.eml`-сообщения из каталога,
+    /// позволяя настроить билдер.
     ///
     /// # Пример
     ///
@@ -118,29 +184,160 @@ impl ReportSet {
     /// # Errors
     ///
     /// Возвращает ошибку, если не удалось прочитать каталог/файлы или `parse_fn` вернул ошибку.
-    pub fn from_dir_with<P, F>(dir: P, mut parse_fn: F) -> Result<Self, ReportError>
+    pub fn from_dir_with<P, F>(dir: P, parse_fn: F) -> Result<Self, ReportError>
     where
         P: AsRef<Path>,
         for<'a> F: FnMut(ReportBuilder<'a>) -> Result<Report, ReportError>,
     {
-        let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<Result<Vec<DirEntry>, _>>()?;
-        // Делаем порядок файлов детерминированным.
-        entries.sort_by_key(DirEntry::path);
-
-        let mut reports = Vec::new();
-        for entry in entries {
-            let path = entry.path();
-            if !is_html_file(&path) {
-                continue;
-            }
+        // Как и раньше, ссылки на файлы верхнего уровня читаются.
+        let options = LoadOptions::new().with_follow_symlinks(true);
+        Self::load_with(dir, &options, parse_fn).map(|outcome| outcome.reports)
+    }
 
-            let file = fs::File::open(&path)?;
-            let raw = RawReport::from_reader(file)?;
-            let report = parse_fn(ReportBuilder::new(&raw))?;
-            reports.push(report);
-        }
+    /// Загружает отчёты из каталога по настройкам обхода с полным набором таблиц.
+    ///
+    /// # Пример
+    ///
+    /// ```
+    /// # use sber_invest_report::{LoadOptions, ReportSet};
+    /// let options = LoadOptions::new()
+    ///     .with_recursive(true)
+    ///     .include("**/*.html")
+    ///     .exclude("multi_account")
+    ///     .with_collect_errors(true);
+    /// let outcome = ReportSet::load("tests/fixtures", &options).unwrap();
+    /// assert!(outcome.is_complete());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку для некорректного шаблона, а без сбора ошибок — и первую ошибку
+    /// чтения или разбора файла.
+    #[inline]
+    pub fn load<P: AsRef<Path>>(dir: P, options: &LoadOptions) -> Result<LoadOutcome, ReportError> {
+        Self::load_with(dir, options, parse_with_default_builder)
+    }
 
-        Ok(Self::new(reports))
+    /// Загружает отчёты из каталога по настройкам обхода, позволяя настроить билдер.
+    ///
+    /// Файлы обходятся в порядке путей. В режиме сбора ошибок файл, который не удалось
+    /// прочитать или разобрать целиком, попадает в [`LoadOutcome::errors`], а загрузка
+    /// продолжается.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку для некорректного шаблона, а без сбора ошибок — и первую ошибку
+    /// чтения каталога, файла или `parse_fn`.
+    pub fn load_with<P, F>(
+        dir: P,
+        options: &LoadOptions,
+        mut parse_fn: F,
+    ) -> Result<LoadOutcome, ReportError>
+    where
+        P: AsRef<Path>,
+        for<'a> F: FnMut(ReportBuilder<'a>) -> Result<Report, ReportError>,
+    {
+        let results = options
+            .source_files(dir.as_ref())?
+            .into_iter()
+            .flat_map(|(path, kind)| match kind {
+                Ok(kind) => parse_source(&path, kind, &mut parse_fn),
+                Err(err) => vec![(path, Err(err))],
+            });
+        LoadOutcome::collect(results, options.collect_errors)
+    }
+
+    /// Загружает и парсит все отчёты из каталога параллельно с полным набором таблиц.
+    ///
+    /// Порядок отчётов тот же, что у [`ReportSet::from_dir`].
+    ///
+    /// # Errors
+    ///
+    /// Возвращает первую по порядку файлов ошибку чтения или разбора.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    pub fn from_dir_par<P: AsRef<Path>>(dir: P) -> Result<Self, ReportError> {
+        let options = LoadOptions::new().with_follow_symlinks(true);
+        Self::load_par_with(dir, &options, parse_with_default_builder)
+            .map(|outcome| outcome.reports)
+    }
+
+    /// Загружает отчёты из каталога по настройкам обхода, разбирая файлы параллельно.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку для некорректного шаблона, а без сбора ошибок — и первую по порядку
+    /// файлов ошибку чтения или разбора.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    pub fn load_par<P: AsRef<Path>>(
+        dir: P,
+        options: &LoadOptions,
+    ) -> Result<LoadOutcome, ReportError> {
+        Self::load_par_with(dir, options, parse_with_default_builder)
+    }
+
+    /// Загружает отчёты из каталога по настройкам обхода, разбирая файлы в пуле потоков `rayon`.
+    ///
+    /// Файлы разбираются независимо, а результаты собираются в порядке путей, поэтому набор
+    /// отчётов и список ошибок совпадают с [`ReportSet::load_with`]. Без сбора ошибок
+    /// разбираются все файлы, но возвращается первая по порядку ошибка.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку для некорректного шаблона, а без сбора ошибок — и первую по порядку
+    /// файлов ошибку чтения каталога, файла или `parse_fn`.
+    #[cfg(feature = "parallel")]
+    pub fn load_par_with<P, F>(
+        dir: P,
+        options: &LoadOptions,
+        parse_fn: F,
+    ) -> Result<LoadOutcome, ReportError>
+    where
+        P: AsRef<Path>,
+        for<'a> F: Fn(ReportBuilder<'a>) -> Result<Report, ReportError> + Sync,
+    {
+        use rayon::prelude::*;
+
+        let results: Vec<_> = options
+            .source_files(dir.as_ref())?
+            .into_par_iter()
+            .flat_map_iter(|(path, kind)| match kind {
+                Ok(kind) => parse_source(&path, kind, &mut &parse_fn),
+                Err(err) => vec![(path, Err(err))],
+            })
+            .collect();
+        LoadOutcome::collect(results, options.collect_errors)
+    }
+
+    /// Загружает и парсит отчёты из одного файла с полным набором таблиц.
+    ///
+    /// Поддерживаются HTML- и XLSX-отчёты, ZIP-архивы и почтовые сообщения `.eml` с отчётами
+    /// во вложениях; файлы с другими расширениями читаются как HTML.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если не удалось прочитать файл, архив или сообщение либо распарсить отчёт.
+    #[inline]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ReportError> {
+        Self::from_file_with(path, parse_with_default_builder)
+    }
+
+    /// Загружает и парсит отчёты из одного файла, позволяя настроить билдер.
+    ///
+    /// # Errors
+    ///
+    /// Возвращает ошибку, если не удалось прочитать файл, архив или сообщение либо `parse_fn`
+    /// вернул ошибку.
+    pub fn from_file_with<P, F>(path: P, mut parse_fn: F) -> Result<Self, ReportError>
+    where
+        P: AsRef<Path>,
+        for<'a> F: FnMut(ReportBuilder<'a>) -> Result<Report, ReportError>,
+    {
+        let path = path.as_ref();
+        let kind = SourceKind::from_path(path).unwrap_or(SourceKind::Html);
+        LoadOutcome::collect(parse_source(path, kind, &mut parse_fn), false)
+            .map(|outcome| outcome.reports)
     }
 
     /// Возвращает итератор по отчётам конкретного договора.
@@ -183,56 +380,153 @@ impl ReportSet {
         CashFlowSummary::new(rows)
     }
 
+    /// Суммирует выплаты доходов по году выплаты и валюте.
+    #[must_use]
+    pub fn income_by_year(&self) -> BTreeMap<(i32, String), IncomeTotals> {
+        let mut map: BTreeMap<(i32, String), IncomeTotals> = BTreeMap::new();
+        for event in self.iter_income_events() {
+            map.entry((event.payment_date.year(), event.currency.clone()))
+                .or_default()
+                .add(event);
+        }
+        map
+    }
+
+    /// Суммирует выплаты доходов по ISIN и валюте.
+    #[must_use]
+    pub fn income_by_isin(&self) -> BTreeMap<(String, String), IncomeTotals> {
+        let mut map: BTreeMap<(String, String), IncomeTotals> = BTreeMap::new();
+        for event in self.iter_income_events() {
+            map.entry((event.isin.clone(), event.currency.clone()))
+                .or_default()
+                .add(event);
+        }
+        map
+    }
+
+    /// Суммирует налоговые блоки отчётов по году окончания периода отчёта.
+    ///
+    /// Налоговый блок брокер считает нарастающим итогом с начала года, поэтому по каждому
+    /// счёту за год берётся только отчёт с самым поздним концом периода (при равенстве —
+    /// сформированный последним); блоки разных счетов складываются.
+    #[must_use]
+    pub fn tax_by_year(&self) -> BTreeMap<i32, TaxTotals> {
+        let mut latest: BTreeMap<(i32, &str), (&ReportMetadata, &TaxSummary)> = BTreeMap::new();
+        for report in &self.reports {
+            let Some(summary) = report.tax_summary() else {
+                continue;
+            };
+            let meta = report.meta();
+            let key = (meta.period_end.year(), meta.account_id.0.as_str());
+            let is_newer = latest.get(&key).is_none_or(|(current, _)| {
+                (meta.period_end, meta.generated_at) >= (current.period_end, current.generated_at)
+            });
+            if is_newer {
+                latest.insert(key, (meta, summary));
+            }
+        }
+
+        let mut map: BTreeMap<i32, TaxTotals> = BTreeMap::new();
+        for ((year, _), (_, summary)) in latest {
+            let totals = map.entry(year).or_default();
+            for row in summary.iter_rows() {
+                totals.add(row);
+            }
+        }
+        map
+    }
+
     /// Агрегирует позиции по ISIN из портфелей всех отчётов.
+    ///
+    /// Паи в реестре владельцев не сообщают стоимость на начало периода и её изменение,
+    /// поэтому в эти суммы не входят: учитываются только их количество и стоимость на конец.
     #[must_use]
     pub fn merge_positions(&self) -> Vec<MergedPosition> {
         let mut map: BTreeMap<String, MergedPosition> = BTreeMap::new();
 
-        for SecurityPosition {
-            isin,
-            name,
-            price_currency,
-            qty_start,
-            qty_end,
-            value_start_no_ai,
-            value_end_no_ai,
-            qty_delta,
-            value_delta,
-            ..
-        } in self.iter_positions()
+        for market in self
+            .reports
+            .iter()
+            .filter_map(Report::portfolio)
+            .flat_map(Portfolio::iter_markets)
         {
-            let entry = map.entry(isin.clone()).or_insert_with(|| MergedPosition {
-                isin: isin.clone(),
-                name: name.clone(),
-                price_currency: price_currency.clone(),
-                qty_start: Decimal::ZERO,
-                qty_end: Decimal::ZERO,
-                value_start_no_ai: Decimal::ZERO,
-                value_end_no_ai: Decimal::ZERO,
-                qty_delta: Decimal::ZERO,
-                value_delta: Decimal::ZERO,
-            });
+            let period_values = market.kind().reports_period_values();
+            for SecurityPosition {
+                isin,
+                name,
+                price_currency,
+                kind,
+                qty_start,
+                qty_end,
+                value_start_no_ai,
+                value_end_no_ai,
+                qty_delta,
+                value_delta,
+                ..
+            } in market.iter_positions()
+            {
+                let entry = map.entry(isin.clone()).or_insert_with(|| MergedPosition {
+                    isin: isin.clone(),
+                    name: name.clone(),
+                    price_currency: price_currency.clone(),
+                    kind: *kind,
+                    qty_start: Decimal::ZERO,
+                    qty_end: Decimal::ZERO,
+                    value_start_no_ai: Decimal::ZERO,
+                    value_end_no_ai: Decimal::ZERO,
+                    qty_delta: Decimal::ZERO,
+                    value_delta: Decimal::ZERO,
+                });
 
-            entry.qty_start += *qty_start;
-            entry.qty_end += *qty_end;
-            entry.value_start_no_ai += *value_start_no_ai;
-            entry.value_end_no_ai += *value_end_no_ai;
-            entry.qty_delta += *qty_delta;
-            entry.value_delta += *value_delta;
+                entry.qty_start += *qty_start;
+                entry.qty_end += *qty_end;
+                entry.value_end_no_ai += *value_end_no_ai;
+                entry.qty_delta += *qty_delta;
+                if period_values {
+                    entry.value_start_no_ai += *value_start_no_ai;
+                    entry.value_delta += *value_delta;
+                }
+            }
         }
 
         map.into_values().collect()
     }
 }
 
-fn is_html_file(path: &Path) -> bool {
-    if !path.is_file() {
-        return false;
-    }
-    matches!(
-        path.extension().and_then(|ext| ext.to_str()),
-        Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm")
-    )
+/// Читает файл-источник и разбирает все отчёты из него.
+///
+/// Результат возвращается по записям источника: для архивов и сообщений путь записи
+/// дописывается к пути файла, чтобы ошибку одной записи можно было собрать отдельно.
+fn parse_source<F>(
+    path: &Path,
+    kind: SourceKind,
+    parse_fn: &mut F,
+) -> Vec<(PathBuf, Result<Vec<Report>, ReportError>)>
+where
+    for<'a> F: FnMut(ReportBuilder<'a>) -> Result<Report, ReportError>,
+{
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) => return vec![(path.to_path_buf(), Err(err.into()))],
+    };
+    sources::read_source(kind, &bytes)
+        .into_iter()
+        .map(|(entry, raw)| {
+            let parsed = raw.and_then(|raw| {
+                // Сводный отчёт по нескольким договорам даёт по отчёту на договор.
+                raw.split_accounts()
+                    .iter()
+                    .map(|part| parse_fn(ReportBuilder::new(part)))
+                    .collect()
+            });
+            let path = if entry.as_os_str().is_empty() {
+                path.to_path_buf()
+            } else {
+                path.join(entry)
+            };
+            (path, parsed)
+        })
+        .collect()
 }
 
 fn parse_with_default_builder(builder: ReportBuilder<'_>) -> Result<Report, ReportError> {
@@ -0,0 +1,204 @@
+//! Источники отчётов помимо отдельных HTML-файлов: XLSX-книги, ZIP-архивы и почтовые сообщения.
+
+use crate::error::ReportError;
+use crate::raw::RawReport;
+use mail_parser::{Message, MessageParser, MimeHeaders};
+use std::io::{Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Вид файла с отчётами, определяемый по расширению.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// Отдельный HTML-отчёт (`.html`, `.htm`).
+    Html,
+    /// ZIP-архив с отчётами.
+    Zip,
+    /// Почтовое сообщение с отчётами во вложениях (`.eml`).
+    Eml,
+    /// Отчёт, выгруженный в XLSX.
+    Xlsx,
+}
+
+impl SourceKind {
+    /// Определяет вид источника по расширению пути; `None` для прочих файлов.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "html" | "htm" => Some(Self::Html),
+            "zip" => Some(Self::Zip),
+            "eml" => Some(Self::Eml),
+            "xlsx" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+}
+
+/// Отчёт, извлечённый из источника, или ошибка его чтения.
+///
+/// Путь — имя записи внутри источника (для вложенных архивов — через `/`); пустой путь
+/// означает сам источник.
+pub type SourceEntry = (PathBuf, Result<RawReport, ReportError>);
+
+/// Наибольший размер распакованной записи архива, который читается в память.
+pub const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Наибольшая глубина вложенности архивов и пересланных сообщений.
+pub const MAX_NESTING_DEPTH: usize = 8;
+
+/// Извлекает отчёты из содержимого источника указанного вида.
+///
+/// Ошибки чтения отдельных записей архива или вложений сообщения возвращаются по записям,
+/// не прерывая чтение остальных.
+pub fn read_source(kind: SourceKind, bytes: &[u8]) -> Vec<SourceEntry> {
+    read_nested(kind, bytes, 0)
+}
+
+/// Читает отчёты из ZIP-архива.
+///
+/// Записи обходятся в порядке имён; вложенные архивы и сообщения разбираются рекурсивно,
+/// остальные файлы пропускаются. Возвращает первую ошибку чтения записи.
+pub fn read_zip<R: Read + Seek>(reader: R) -> Result<Vec<RawReport>, ReportError> {
+    zip_entries(reader, 0)?
+        .into_iter()
+        .map(|(_, raw)| raw)
+        .collect()
+}
+
+/// Читает отчёты из вложений почтового сообщения (MIME).
+///
+/// Вложения декодируются из base64/quoted-printable; учитываются HTML-файлы, архивы
+/// и пересланные сообщения в порядке следования. Возвращает первую ошибку чтения вложения.
+pub fn read_eml(bytes: &[u8]) -> Result<Vec<RawReport>, ReportError> {
+    eml_entries(bytes, 0)?
+        .into_iter()
+        .map(|(_, raw)| raw)
+        .collect()
+}
+
+fn read_nested(kind: SourceKind, bytes: &[u8], depth: usize) -> Vec<SourceEntry> {
+    let entries = match kind {
+        SourceKind::Html => Ok(vec![(PathBuf::new(), Ok(RawReport::from_bytes(bytes)))]),
+        SourceKind::Zip => zip_entries(Cursor::new(bytes), depth),
+        SourceKind::Eml => eml_entries(bytes, depth),
+        SourceKind::Xlsx => Ok(vec![(
+            PathBuf::new(),
+            RawReport::from_xlsx(Cursor::new(bytes)),
+        )]),
+    };
+    entries.unwrap_or_else(|err| vec![(PathBuf::new(), Err(err))])
+}
+
+fn zip_entries<R: Read + Seek>(reader: R, depth: usize) -> Result<Vec<SourceEntry>, ReportError> {
+    check_depth(depth)?;
+    let mut archive = ZipArchive::new(reader).map_err(archive_error)?;
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+
+    let mut entries = Vec::new();
+    for name in names {
+        let Some(kind) = SourceKind::from_path(Path::new(&name)) else {
+            continue;
+        };
+        let bytes = match archive.by_name(&name) {
+            Ok(entry) if entry.is_dir() => continue,
+            Ok(entry) => read_limited(entry, MAX_ENTRY_SIZE),
+            Err(err) => Err(archive_error(err)),
+        };
+        match bytes {
+            Ok(bytes) => entries.extend(nest(&name, read_nested(kind, &bytes, depth + 1))),
+            Err(err) => entries.push((PathBuf::from(name), Err(err))),
+        }
+    }
+    Ok(entries)
+}
+
+fn eml_entries(bytes: &[u8], depth: usize) -> Result<Vec<SourceEntry>, ReportError> {
+    check_depth(depth)?;
+    let message = MessageParser::default()
+        .parse(bytes)
+        .ok_or_else(|| ReportError::Mail("not a MIME message".to_string()))?;
+    Ok(message_entries(&message, depth))
+}
+
+fn message_entries(message: &Message<'_>, depth: usize) -> Vec<SourceEntry> {
+    let mut entries = Vec::new();
+    for (index, part) in message.attachments().enumerate() {
+        let name = part
+            .attachment_name()
+            .map_or_else(|| format!("attachment-{}", index + 1), str::to_string);
+        if let Some(nested) = part.message() {
+            let nested = check_depth(depth + 1).map_or_else(
+                |err| vec![(PathBuf::new(), Err(err))],
+                |()| message_entries(nested, depth + 1),
+            );
+            entries.extend(nest(&name, nested));
+            continue;
+        }
+        let kind = part
+            .attachment_name()
+            .and_then(|name| SourceKind::from_path(Path::new(name)))
+            .or_else(|| {
+                part.is_content_type("text", "html")
+                    .then_some(SourceKind::Html)
+            });
+        if let Some(kind) = kind {
+            entries.extend(nest(&name, read_nested(kind, part.contents(), depth + 1)));
+        }
+    }
+    entries
+}
+
+/// Дописывает имя записи контейнера перед путями извлечённых из неё отчётов.
+fn nest(name: &str, entries: Vec<SourceEntry>) -> impl Iterator<Item = SourceEntry> + '_ {
+    entries.into_iter().map(move |(path, raw)| {
+        let path = if path.as_os_str().is_empty() {
+            PathBuf::from(name)
+        } else {
+            Path::new(name).join(path)
+        };
+        (path, raw)
+    })
+}
+
+/// Читает запись целиком, если она не больше `limit` байт.
+fn read_limited<R: Read>(reader: R, limit: u64) -> Result<Vec<u8>, ReportError> {
+    let mut bytes = Vec::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > limit {
+        return Err(ReportError::Archive(format!("entry exceeds {limit} bytes")));
+    }
+    Ok(bytes)
+}
+
+fn check_depth(depth: usize) -> Result<(), ReportError> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(ReportError::Archive(format!(
+            "nesting exceeds {MAX_NESTING_DEPTH} levels"
+        )));
+    }
+    Ok(())
+}
+
+fn archive_error(err: zip::result::ZipError) -> ReportError {
+    match err {
+        zip::result::ZipError::Io(err) => ReportError::Io(err),
+        err => ReportError::Archive(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_entries_over_size_limit() {
+        assert_eq!(read_limited(&b"12345"[..], 5).expect("fits"), b"12345");
+        assert!(matches!(
+            read_limited(&b"123456"[..], 5),
+            Err(ReportError::Archive(_))
+        ));
+    }
+}
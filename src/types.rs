@@ -1,7 +1,9 @@
 //! Доменные типы и структуры, соответствующие разделам отчёта.
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fmt;
 
 /// Денежное значение, используем `Decimal` для точных расчётов.
 pub type Money = Decimal;
@@ -110,6 +112,12 @@ pub enum CashFlowKind {
     BrokerFee,
     /// Комиссия биржи.
     ExchangeFee,
+    /// Доход по сделкам РЕПО.
+    RepoIncome,
+    /// Вариационная маржа по срочным контрактам.
+    VariationMargin,
+    /// Плата за использование необеспеченных позиций (маржинальное кредитование).
+    MarginFee,
     /// Исходящий остаток.
     ClosingBalance,
     /// Неизвестный тип строки.
@@ -155,6 +163,76 @@ impl CashFlowSummary {
     }
 }
 
+/// Строка таблицы «Движение денежных средств за период».
+#[derive(Debug, Clone)]
+pub struct CashOperation {
+    /// Дата операции.
+    pub date: NaiveDate,
+    /// Торговая площадка.
+    pub venue: String,
+    /// Описание операции.
+    pub description: String,
+    /// Валюта.
+    pub currency: String,
+    /// Сумма зачисления.
+    pub credit: Money,
+    /// Сумма списания.
+    pub debit: Money,
+    /// Остаток после операции, если отчёт его содержит.
+    pub balance: Option<Money>,
+}
+
+impl CashOperation {
+    /// Возвращает сальдо операции: зачисление минус списание.
+    #[must_use]
+    pub fn net(&self) -> Money {
+        self.credit - self.debit
+    }
+}
+
+/// Таблица движения денежных средств с отдельными операциями.
+#[derive(Debug, Clone)]
+pub struct CashOperationsTable {
+    /// Операции в порядке следования в отчёте.
+    pub(crate) rows: Vec<CashOperation>,
+}
+
+impl CashOperationsTable {
+    /// Создаёт таблицу операций с денежными средствами.
+    #[must_use]
+    pub const fn new(rows: Vec<CashOperation>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает операции.
+    #[must_use]
+    pub fn rows(&self) -> &[CashOperation] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по операциям.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &CashOperation> {
+        self.rows.iter()
+    }
+}
+
+/// Вид ценной бумаги для группировки портфеля.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SecurityKind {
+    /// Акция.
+    Share,
+    /// Облигация.
+    Bond,
+    /// Пай биржевого или паевого фонда (ETF, БПИФ, ПИФ).
+    FundUnit,
+    /// Депозитарная расписка.
+    DepositaryReceipt,
+    /// Структурная нота или структурная облигация.
+    StructuredNote,
+    /// Вид бумаги определить не удалось.
+    Unknown,
+}
+
 /// Позиция ценной бумаги на начало и конец периода.
 #[derive(Debug, Clone)]
 pub struct SecurityPosition {
@@ -164,6 +242,8 @@ pub struct SecurityPosition {
     pub isin: String,
     /// Валюта цены.
     pub price_currency: String,
+    /// Вид бумаги по справочнику, названию и ISIN.
+    pub kind: SecurityKind,
 
     /// Количество на начало.
     pub qty_start: Money,
@@ -182,6 +262,8 @@ pub struct SecurityPosition {
     pub nominal_end: Money,
     /// Цена на конец.
     pub price_end: Money,
+    /// Дата цены на конец периода; для паёв у регистратора — дата расчёта стоимости пая.
+    pub price_date: Option<NaiveDate>,
     /// Стоимость без НКД на конец.
     pub value_end_no_ai: Money,
     /// НКД на конец.
@@ -198,6 +280,147 @@ pub struct SecurityPosition {
     pub planned_out_qty: Money,
     /// Плановый исходящий остаток.
     pub planned_end_qty: Money,
+    /// Заблокированное количество (например, в иностранной инфраструктуре).
+    pub qty_blocked: Money,
+}
+
+impl SecurityPosition {
+    /// Возвращает `true`, если на конец периода позиция короткая (необеспеченная).
+    #[must_use]
+    pub fn is_short(&self) -> bool {
+        self.qty_end < Decimal::ZERO
+    }
+
+    /// Возвращает `true`, если часть позиции или вся позиция заблокирована.
+    #[must_use]
+    pub fn is_blocked(&self) -> bool {
+        self.qty_blocked > Decimal::ZERO
+    }
+
+    /// Возвращает стоимость без НКД на конец периода за вычетом заблокированной доли.
+    ///
+    /// Доля считается пропорционально количеству; блокировка сверх остатка обнуляет стоимость.
+    #[must_use]
+    pub fn unblocked_value_end_no_ai(&self) -> Money {
+        if !self.is_blocked() {
+            return self.value_end_no_ai;
+        }
+        if self.qty_blocked >= self.qty_end {
+            return Money::ZERO;
+        }
+        self.value_end_no_ai * (self.qty_end - self.qty_blocked) / self.qty_end
+    }
+}
+
+/// Строка таблицы «Справочник ценных бумаг».
+#[derive(Debug, Clone)]
+pub struct SecurityInfo {
+    /// Наименование бумаги.
+    pub name: String,
+    /// Код бумаги в торговой системе.
+    pub code: String,
+    /// ISIN.
+    pub isin: String,
+    /// Эмитент.
+    pub issuer: String,
+    /// Вид, категория, тип и иная информация (например, «Акция обыкновенная»).
+    pub instrument_type: String,
+    /// Номер государственной регистрации выпуска (выпуск, транш, серия).
+    pub registration_number: String,
+    /// Валюта номинала, если отчёт её содержит.
+    pub face_value_currency: Option<String>,
+}
+
+/// Справочник ценных бумаг отчёта с поиском по ISIN и по коду бумаги.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityReference {
+    /// Записи справочника по ISIN (или по коду, если ISIN не указан).
+    pub(crate) by_isin: BTreeMap<String, SecurityInfo>,
+    /// Ключ записи в `by_isin` по коду бумаги в торговой системе.
+    pub(crate) by_code: BTreeMap<String, String>,
+}
+
+impl SecurityReference {
+    /// Создаёт справочник из списка записей.
+    ///
+    /// Записи без ISIN индексируются по коду бумаги; при повторе ключа остаётся последняя запись.
+    #[must_use]
+    pub fn new(rows: Vec<SecurityInfo>) -> Self {
+        let mut by_isin = BTreeMap::new();
+        let mut by_code = BTreeMap::new();
+        for info in rows {
+            let key = if info.isin.is_empty() {
+                info.code.clone()
+            } else {
+                info.isin.clone()
+            };
+            if !info.code.is_empty() {
+                by_code.insert(info.code.clone(), key.clone());
+            }
+            by_isin.insert(key, info);
+        }
+        Self { by_isin, by_code }
+    }
+
+    /// Возвращает запись справочника по ISIN.
+    #[must_use]
+    pub fn get(&self, isin: &str) -> Option<&SecurityInfo> {
+        self.by_isin.get(isin)
+    }
+
+    /// Ищет запись справочника по коду бумаги в торговой системе.
+    #[must_use]
+    pub fn by_code(&self, code: &str) -> Option<&SecurityInfo> {
+        self.by_code.get(code).and_then(|key| self.by_isin.get(key))
+    }
+
+    /// Возвращает ISIN бумаги по её коду в торговой системе, если он указан в справочнике.
+    #[must_use]
+    pub fn isin_by_code(&self, code: &str) -> Option<&str> {
+        self.by_code(code)
+            .map(|info| info.isin.as_str())
+            .filter(|isin| !isin.is_empty())
+    }
+
+    /// Возвращает итератор по записям справочника в порядке ISIN.
+    pub fn iter(&self) -> impl Iterator<Item = &SecurityInfo> {
+        self.by_isin.values()
+    }
+
+    /// Возвращает количество записей.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.by_isin.len()
+    }
+
+    /// Возвращает `true`, если справочник пуст.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.by_isin.is_empty()
+    }
+}
+
+/// Вид блока портфеля.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PortfolioMarketKind {
+    /// Биржевая площадка (блок «Площадка:»).
+    #[default]
+    Exchange,
+    /// Паи, учитываемые в реестре владельцев у управляющей компании.
+    ///
+    /// Реестр сообщает только количество паёв, расчётную стоимость пая и стоимость на конец
+    /// периода: номинал, цена и стоимость на начало, НКД и изменение стоимости в позициях
+    /// такого блока не заполняются и равны нулю.
+    Registrar,
+}
+
+impl PortfolioMarketKind {
+    /// Возвращает `true`, если позиции блока содержат стоимость на начало периода и её
+    /// изменение.
+    #[must_use]
+    pub const fn reports_period_values(self) -> bool {
+        matches!(self, Self::Exchange)
+    }
 }
 
 /// Набор позиций по конкретной торговой площадке.
@@ -205,15 +428,28 @@ pub struct SecurityPosition {
 pub struct PortfolioMarket {
     /// Название площадки.
     pub(crate) name: String,
+    /// Вид блока.
+    pub(crate) kind: PortfolioMarketKind,
     /// Позиции на площадке.
     pub(crate) positions: Vec<SecurityPosition>,
 }
 
 impl PortfolioMarket {
-    /// Создаёт рыночный блок портфеля.
+    /// Создаёт рыночный блок портфеля биржевой площадки.
     #[must_use]
     pub const fn new(name: String, positions: Vec<SecurityPosition>) -> Self {
-        Self { name, positions }
+        Self {
+            name,
+            kind: PortfolioMarketKind::Exchange,
+            positions,
+        }
+    }
+
+    /// Возвращает копию блока с заменённым видом.
+    #[must_use]
+    pub const fn with_kind(mut self, kind: PortfolioMarketKind) -> Self {
+        self.kind = kind;
+        self
     }
 
     /// Возвращает название торговой площадки.
@@ -222,6 +458,12 @@ impl PortfolioMarket {
         &self.name
     }
 
+    /// Возвращает вид блока.
+    #[must_use]
+    pub const fn kind(&self) -> PortfolioMarketKind {
+        self.kind
+    }
+
     /// Возвращает позиции площадки.
     #[must_use]
     pub fn positions(&self) -> &[SecurityPosition] {
@@ -265,6 +507,43 @@ impl Portfolio {
             .iter()
             .flat_map(PortfolioMarket::iter_positions)
     }
+
+    /// Возвращает позицию по ISIN (первую, если бумага есть на нескольких площадках).
+    #[must_use]
+    pub fn position(&self, isin: &str) -> Option<&SecurityPosition> {
+        self.iter_positions().find(|position| position.isin == isin)
+    }
+
+    /// Возвращает итератор по позициям вместе с записями справочника ценных бумаг.
+    pub fn iter_positions_with_info<'a>(
+        &'a self,
+        reference: &'a SecurityReference,
+    ) -> impl Iterator<Item = (&'a SecurityPosition, Option<&'a SecurityInfo>)> {
+        self.iter_positions()
+            .map(|position| (position, reference.get(&position.isin)))
+    }
+
+    /// Суммирует стоимость без НКД на конец периода по виду бумаги и валюте цены.
+    #[must_use]
+    pub fn value_by_kind(&self) -> BTreeMap<(SecurityKind, String), Money> {
+        let mut map: BTreeMap<(SecurityKind, String), Money> = BTreeMap::new();
+        for position in self.iter_positions() {
+            *map.entry((position.kind, position.price_currency.clone()))
+                .or_insert(Decimal::ZERO) += position.value_end_no_ai;
+        }
+        map
+    }
+
+    /// Возвращает итератор по коротким позициям.
+    pub fn short_positions(&self) -> impl Iterator<Item = &SecurityPosition> {
+        self.iter_positions().filter(|position| position.is_short())
+    }
+
+    /// Возвращает `true`, если в портфеле есть короткие позиции.
+    #[must_use]
+    pub fn has_short_positions(&self) -> bool {
+        self.short_positions().next().is_some()
+    }
 }
 
 /// Лимит ИИС: фиксированная сумма или отсутствие ограничений.
@@ -336,6 +615,936 @@ impl IisContributionsTable {
     }
 }
 
+/// Направление сделки купли/продажи.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TradeSide {
+    /// Покупка.
+    Buy,
+    /// Продажа.
+    Sell,
+    /// Направление не распознано.
+    Unknown,
+}
+
+/// Строка таблицы «Сделки купли/продажи ценных бумаг».
+#[derive(Debug, Clone)]
+pub struct Trade {
+    /// Дата заключения сделки.
+    pub trade_date: NaiveDate,
+    /// Дата расчётов по сделке.
+    pub settlement_date: NaiveDate,
+    /// Время заключения, если указано.
+    pub trade_time: Option<NaiveTime>,
+    /// Торговая площадка.
+    pub venue: String,
+    /// Наименование бумаги.
+    pub name: String,
+    /// Код бумаги в торговой системе.
+    pub code: String,
+    /// ISIN из справочника ценных бумаг, если код удалось сопоставить.
+    pub isin: Option<String>,
+    /// Валюта сделки.
+    pub currency: String,
+    /// Направление сделки.
+    pub side: TradeSide,
+    /// Количество, шт.
+    pub quantity: Money,
+    /// Цена.
+    pub price: Money,
+    /// Сумма сделки без НКД.
+    pub amount: Money,
+    /// НКД.
+    pub accrued_interest: Money,
+    /// Комиссия брокера.
+    pub broker_fee: Money,
+    /// Комиссия биржи.
+    pub exchange_fee: Money,
+    /// Номер сделки.
+    pub trade_number: String,
+    /// Комментарий.
+    pub comment: String,
+    /// Статус сделки на конец периода (`З`, `О`, `И` и т.д.).
+    pub status: String,
+}
+
+/// Таблица сделок купли/продажи ценных бумаг.
+#[derive(Debug, Clone)]
+pub struct TradesTable {
+    /// Сделки в порядке следования в отчёте.
+    pub(crate) rows: Vec<Trade>,
+}
+
+impl TradesTable {
+    /// Создаёт таблицу сделок.
+    #[must_use]
+    pub const fn new(rows: Vec<Trade>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает сделки.
+    #[must_use]
+    pub fn rows(&self) -> &[Trade] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по сделкам.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &Trade> {
+        self.rows.iter()
+    }
+}
+
+/// Валютная пара сделки на валютном рынке.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CurrencyPair {
+    /// Базовая валюта (покупается или продаётся).
+    pub base: String,
+    /// Сопряжённая валюта, в которой выражена цена.
+    pub quote: String,
+}
+
+impl CurrencyPair {
+    /// Возвращает `true`, если валюта входит в пару.
+    #[must_use]
+    pub fn involves(&self, currency: &str) -> bool {
+        self.base == currency || self.quote == currency
+    }
+}
+
+impl fmt::Display for CurrencyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.base, self.quote)
+    }
+}
+
+/// Строка таблицы «Сделки с валютными инструментами».
+#[derive(Debug, Clone)]
+pub struct FxTrade {
+    /// Исходный код валютного инструмента (например, `CNYRUB_TOM`).
+    pub instrument: String,
+    /// Валютная пара инструмента.
+    pub pair: CurrencyPair,
+    /// Дата заключения сделки.
+    pub trade_date: NaiveDate,
+    /// Дата расчётов по сделке.
+    pub settlement_date: NaiveDate,
+    /// Время заключения, если указано.
+    pub trade_time: Option<NaiveTime>,
+    /// Направление сделки относительно базовой валюты.
+    pub side: TradeSide,
+    /// Количество базовой валюты лота.
+    pub quantity: Money,
+    /// Курс сделки.
+    pub rate: Money,
+    /// Сумма сделки в сопряжённой валюте.
+    pub amount: Money,
+    /// Комиссия брокера, руб.
+    pub broker_fee: Money,
+    /// Комиссия биржи, руб.
+    pub exchange_fee: Money,
+    /// Номер сделки.
+    pub trade_number: String,
+    /// Комментарий.
+    pub comment: String,
+}
+
+/// Таблица сделок с валютными инструментами.
+#[derive(Debug, Clone)]
+pub struct FxTradesTable {
+    /// Сделки в порядке следования в отчёте.
+    pub(crate) rows: Vec<FxTrade>,
+}
+
+impl FxTradesTable {
+    /// Создаёт таблицу валютных сделок.
+    #[must_use]
+    pub const fn new(rows: Vec<FxTrade>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает сделки.
+    #[must_use]
+    pub fn rows(&self) -> &[FxTrade] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по сделкам.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &FxTrade> {
+        self.rows.iter()
+    }
+
+    /// Возвращает сделки, в которых участвует указанная валюта.
+    ///
+    /// Позволяет найти сделки, сформировавшие строки сводки ДС в этой валюте.
+    pub fn iter_for_currency<'a>(&'a self, currency: &'a str) -> impl Iterator<Item = &'a FxTrade> {
+        self.rows
+            .iter()
+            .filter(move |row| row.pair.involves(currency))
+    }
+}
+
+/// Тип операции в таблице движения ценных бумаг.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SecurityMovementKind {
+    /// Исполнение сделки купли/продажи.
+    Trade,
+    /// Перевод бумаг между счетами, депозитариями или брокерами.
+    Transfer,
+    /// Конвертация выпуска.
+    Conversion,
+    /// Дробление или консолидация.
+    Split,
+    /// Погашение или амортизация выпуска.
+    Redemption,
+    /// Прочие депозитарные операции.
+    DepositoryOperation,
+    /// Неизвестный тип операции.
+    Unknown,
+}
+
+/// Строка таблицы «Движение ценных бумаг».
+#[derive(Debug, Clone)]
+pub struct SecurityMovement {
+    /// Дата операции.
+    pub date: NaiveDate,
+    /// Торговая площадка.
+    pub venue: String,
+    /// Наименование бумаги.
+    pub name: String,
+    /// ISIN.
+    pub isin: String,
+    /// Исходное название операции из отчёта.
+    pub operation: String,
+    /// Классификация операции.
+    pub kind: SecurityMovementKind,
+    /// Зачислено, шт.
+    pub qty_in: Money,
+    /// Списано, шт.
+    pub qty_out: Money,
+    /// Основание операции.
+    pub reason: String,
+    /// Заявленная стоимость приобретения из внебиржевой операции, если она найдена.
+    pub acquisition: Option<AcquisitionCost>,
+}
+
+impl SecurityMovement {
+    /// Возвращает изменение количества: зачисление минус списание.
+    #[must_use]
+    pub fn qty_net(&self) -> Money {
+        self.qty_in - self.qty_out
+    }
+}
+
+/// Таблица движения ценных бумаг.
+#[derive(Debug, Clone)]
+pub struct SecurityMovementsTable {
+    /// Операции в порядке следования в отчёте.
+    pub(crate) rows: Vec<SecurityMovement>,
+}
+
+impl SecurityMovementsTable {
+    /// Создаёт таблицу движения ценных бумаг.
+    #[must_use]
+    pub const fn new(rows: Vec<SecurityMovement>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает операции.
+    #[must_use]
+    pub fn rows(&self) -> &[SecurityMovement] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по операциям.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &SecurityMovement> {
+        self.rows.iter()
+    }
+
+    /// Суммирует изменение количества по ISIN для всех операций, кроме сделок.
+    ///
+    /// Вместе со сделками объясняет `SecurityPosition::qty_delta` портфеля.
+    #[must_use]
+    pub fn non_trade_net_by_isin(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in self
+            .rows
+            .iter()
+            .filter(|row| row.kind != SecurityMovementKind::Trade)
+        {
+            *map.entry(row.isin.clone()).or_insert(Decimal::ZERO) += row.qty_net();
+        }
+        map
+    }
+}
+
+/// Вид дохода по ценной бумаге.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IncomeKind {
+    /// Дивиденды.
+    Dividend,
+    /// Купонный доход.
+    Coupon,
+    /// Погашение номинала.
+    Redemption,
+    /// Частичное погашение (амортизация) номинала.
+    PartialAmortization,
+    /// Неизвестный вид выплаты.
+    Unknown,
+}
+
+/// Выплата дохода по ценной бумаге с удержанным налогом.
+#[derive(Debug, Clone)]
+pub struct IncomeEvent {
+    /// Дата выплаты.
+    pub payment_date: NaiveDate,
+    /// Наименование бумаги.
+    pub name: String,
+    /// ISIN.
+    pub isin: String,
+    /// Классификация выплаты.
+    pub kind: IncomeKind,
+    /// Исходное описание вида выплаты.
+    pub description: String,
+    /// Сумма до налогообложения.
+    pub gross: Money,
+    /// Удержанный налог.
+    pub tax_withheld: Money,
+    /// Сумма к выплате после налога.
+    pub net: Money,
+    /// Валюта выплаты.
+    pub currency: String,
+}
+
+/// Таблица выплат доходов по ценным бумагам.
+#[derive(Debug, Clone)]
+pub struct IncomeEventsTable {
+    /// Выплаты в порядке следования в отчёте.
+    pub(crate) rows: Vec<IncomeEvent>,
+}
+
+impl IncomeEventsTable {
+    /// Создаёт таблицу выплат доходов.
+    #[must_use]
+    pub const fn new(rows: Vec<IncomeEvent>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает выплаты.
+    #[must_use]
+    pub fn rows(&self) -> &[IncomeEvent] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по выплатам.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &IncomeEvent> {
+        self.rows.iter()
+    }
+}
+
+/// Суммы выплат доходов: до налога, налог и к выплате.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IncomeTotals {
+    /// Сумма до налогообложения.
+    pub gross: Money,
+    /// Удержанный налог.
+    pub tax_withheld: Money,
+    /// Сумма к выплате.
+    pub net: Money,
+}
+
+impl IncomeTotals {
+    /// Добавляет выплату к итогам.
+    pub fn add(&mut self, event: &IncomeEvent) {
+        self.gross += event.gross;
+        self.tax_withheld += event.tax_withheld;
+        self.net += event.net;
+    }
+}
+
+/// Строка налогового блока отчёта: код дохода и ставка НДФЛ.
+#[derive(Debug, Clone)]
+pub struct TaxRow {
+    /// Код дохода (например, `1530` или `1010`).
+    pub income_code: String,
+    /// Ставка налога, %.
+    pub rate: Money,
+    /// Налоговая база.
+    pub tax_base: Money,
+    /// Исчисленный налог.
+    pub calculated: Money,
+    /// Удержанный налог.
+    pub withheld: Money,
+    /// Налог к удержанию.
+    pub due: Money,
+}
+
+/// Суммы налогового блока: база, исчисленный, удержанный налог и налог к удержанию.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxTotals {
+    /// Налоговая база.
+    pub tax_base: Money,
+    /// Исчисленный налог.
+    pub calculated: Money,
+    /// Удержанный налог.
+    pub withheld: Money,
+    /// Налог к удержанию.
+    pub due: Money,
+}
+
+impl TaxTotals {
+    /// Добавляет строку налогового блока к итогам.
+    pub fn add(&mut self, row: &TaxRow) {
+        self.tax_base += row.tax_base;
+        self.calculated += row.calculated;
+        self.withheld += row.withheld;
+        self.due += row.due;
+    }
+}
+
+/// Налоговый блок отчёта (НДФЛ за период).
+#[derive(Debug, Clone)]
+pub struct TaxSummary {
+    /// Строки по кодам дохода и ставкам.
+    pub(crate) rows: Vec<TaxRow>,
+}
+
+impl TaxSummary {
+    /// Создаёт налоговый блок.
+    #[must_use]
+    pub const fn new(rows: Vec<TaxRow>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает строки налогового блока.
+    #[must_use]
+    pub fn rows(&self) -> &[TaxRow] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по строкам налогового блока.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &TaxRow> {
+        self.rows.iter()
+    }
+
+    /// Возвращает итоги по всем строкам.
+    #[must_use]
+    pub fn totals(&self) -> TaxTotals {
+        let mut totals = TaxTotals::default();
+        for row in &self.rows {
+            totals.add(row);
+        }
+        totals
+    }
+
+    /// Суммирует налоговую базу по ставкам налога.
+    #[must_use]
+    pub fn base_by_rate(&self) -> BTreeMap<Money, Money> {
+        let mut map: BTreeMap<Money, Money> = BTreeMap::new();
+        for row in &self.rows {
+            *map.entry(row.rate).or_insert(Decimal::ZERO) += row.tax_base;
+        }
+        map
+    }
+}
+
+/// Направление сделки РЕПО по первой части.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepoDirection {
+    /// Прямое РЕПО: продажа бумаг по первой части, привлечение денег.
+    Direct,
+    /// Обратное РЕПО: покупка бумаг по первой части, размещение денег.
+    Reverse,
+    /// Направление не распознано.
+    Unknown,
+}
+
+/// Одна часть сделки РЕПО.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoLeg {
+    /// Дата исполнения части; пусто, если часть ещё не исполнена.
+    pub date: Option<NaiveDate>,
+    /// Цена.
+    pub price: Money,
+    /// Сумма.
+    pub amount: Money,
+}
+
+/// Сделка РЕПО, включая автоматическое РЕПО с ЦК для свободных денег.
+#[derive(Debug, Clone)]
+pub struct RepoDeal {
+    /// Дата заключения сделки.
+    pub trade_date: NaiveDate,
+    /// Наименование бумаги.
+    pub name: String,
+    /// ISIN.
+    pub isin: String,
+    /// Направление сделки.
+    pub direction: RepoDirection,
+    /// Количество бумаг, шт.
+    pub quantity: Money,
+    /// Первая часть сделки.
+    pub first_leg: RepoLeg,
+    /// Вторая часть сделки.
+    pub second_leg: RepoLeg,
+    /// Ставка РЕПО, % годовых.
+    pub rate: Money,
+    /// Доход (расход со знаком минус) по сделке.
+    pub income: Money,
+    /// Валюта сделки.
+    pub currency: String,
+    /// Номер сделки.
+    pub trade_number: String,
+}
+
+/// Таблица сделок РЕПО.
+#[derive(Debug, Clone)]
+pub struct RepoDealsTable {
+    /// Сделки в порядке следования в отчёте.
+    pub(crate) rows: Vec<RepoDeal>,
+}
+
+impl RepoDealsTable {
+    /// Создаёт таблицу сделок РЕПО.
+    #[must_use]
+    pub const fn new(rows: Vec<RepoDeal>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает сделки.
+    #[must_use]
+    pub fn rows(&self) -> &[RepoDeal] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по сделкам.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &RepoDeal> {
+        self.rows.iter()
+    }
+
+    /// Суммирует доход по сделкам в разрезе валют.
+    #[must_use]
+    pub fn income_by_currency(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in &self.rows {
+            *map.entry(row.currency.clone()).or_insert(Decimal::ZERO) += row.income;
+        }
+        map
+    }
+}
+
+/// Открытая позиция по срочному контракту (FORTS).
+#[derive(Debug, Clone)]
+pub struct DerivativePosition {
+    /// Код контракта.
+    pub contract_code: String,
+    /// Тип контракта (фьючерс, опцион).
+    pub contract_type: String,
+    /// Базовый актив.
+    pub base_asset: String,
+    /// Дата исполнения контракта.
+    pub expiration_date: Option<NaiveDate>,
+    /// Позиция на начало периода; короткая позиция отрицательна.
+    pub qty_start: Money,
+    /// Позиция на конец периода; короткая позиция отрицательна.
+    pub qty_end: Money,
+    /// Расчётная цена на конец периода.
+    pub settlement_price: Money,
+    /// Гарантийное обеспечение под позицию.
+    pub guarantee_collateral: Money,
+    /// Валюта расчётов.
+    pub currency: String,
+}
+
+/// Начисление или списание вариационной маржи за торговый день.
+#[derive(Debug, Clone)]
+pub struct VariationMarginRow {
+    /// Дата клиринга.
+    pub date: NaiveDate,
+    /// Код контракта.
+    pub contract_code: String,
+    /// Вариационная маржа; списание отрицательно.
+    pub variation_margin: Money,
+    /// Биржевой сбор.
+    pub exchange_fee: Money,
+    /// Валюта расчётов.
+    pub currency: String,
+}
+
+/// Секция срочного рынка: открытые позиции и вариационная маржа.
+#[derive(Debug, Clone, Default)]
+pub struct DerivativesSection {
+    /// Открытые позиции.
+    pub(crate) positions: Vec<DerivativePosition>,
+    /// Движение вариационной маржи.
+    pub(crate) variation_margin: Vec<VariationMarginRow>,
+}
+
+impl DerivativesSection {
+    /// Создаёт секцию срочного рынка.
+    #[must_use]
+    pub const fn new(
+        positions: Vec<DerivativePosition>,
+        variation_margin: Vec<VariationMarginRow>,
+    ) -> Self {
+        Self {
+            positions,
+            variation_margin,
+        }
+    }
+
+    /// Возвращает открытые позиции.
+    #[must_use]
+    pub fn positions(&self) -> &[DerivativePosition] {
+        &self.positions
+    }
+
+    /// Возвращает строки вариационной маржи.
+    #[must_use]
+    pub fn variation_margin(&self) -> &[VariationMarginRow] {
+        &self.variation_margin
+    }
+
+    /// Суммирует гарантийное обеспечение по валютам.
+    #[must_use]
+    pub fn guarantee_collateral_by_currency(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in &self.positions {
+            *map.entry(row.currency.clone()).or_insert(Decimal::ZERO) += row.guarantee_collateral;
+        }
+        map
+    }
+
+    /// Возвращает финансовый результат по валютам: вариационная маржа за вычетом биржевых сборов.
+    #[must_use]
+    pub fn net_result_by_currency(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in &self.variation_margin {
+            *map.entry(row.currency.clone()).or_insert(Decimal::ZERO) +=
+                row.variation_margin - row.exchange_fee;
+        }
+        map
+    }
+}
+
+/// Источник незавершённых расчётов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettlementSource {
+    /// Плановые зачисления и списания по таблице «Денежные средства».
+    BrokerCash,
+    /// Требования и обязательства на валютном рынке.
+    FxMarket,
+}
+
+/// Незавершённые расчёты (например, по сделкам T+1) в одной валюте.
+#[derive(Debug, Clone)]
+pub struct PendingSettlement {
+    /// Источник строки.
+    pub source: SettlementSource,
+    /// Торговая площадка, если указана.
+    pub venue: Option<String>,
+    /// Валюта.
+    pub currency: String,
+    /// Дата исполнения, если указана.
+    pub settlement_date: Option<NaiveDate>,
+    /// Плановые зачисления (требования).
+    pub incoming: Money,
+    /// Плановые списания (обязательства).
+    pub outgoing: Money,
+}
+
+impl PendingSettlement {
+    /// Возвращает чистое плановое изменение остатка.
+    #[must_use]
+    pub fn net(&self) -> Money {
+        self.incoming - self.outgoing
+    }
+}
+
+/// Список незавершённых расчётов.
+#[derive(Debug, Clone)]
+pub struct PendingSettlements {
+    /// Строки в порядке следования в отчёте.
+    pub(crate) rows: Vec<PendingSettlement>,
+}
+
+impl PendingSettlements {
+    /// Создаёт список незавершённых расчётов.
+    #[must_use]
+    pub const fn new(rows: Vec<PendingSettlement>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает строки.
+    #[must_use]
+    pub fn rows(&self) -> &[PendingSettlement] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по строкам.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &PendingSettlement> {
+        self.rows.iter()
+    }
+
+    /// Суммирует чистое плановое изменение по валютам.
+    #[must_use]
+    pub fn net_by_currency(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in &self.rows {
+            *map.entry(row.currency.clone()).or_insert(Decimal::ZERO) += row.net();
+        }
+        map
+    }
+}
+
+/// Вид необеспеченной позиции.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarginExposureKind {
+    /// Заём денежных средств (отрицательный остаток).
+    BorrowedCash,
+    /// Короткая позиция по ценной бумаге.
+    ShortSecurity,
+    /// Вид задолженности не распознан.
+    Unknown,
+}
+
+/// Строка таблицы необеспеченных позиций на конец периода.
+#[derive(Debug, Clone)]
+pub struct MarginPosition {
+    /// Инструмент: валюта займа или наименование бумаги.
+    pub instrument: String,
+    /// Вид позиции.
+    pub kind: MarginExposureKind,
+    /// Количество бумаг или сумма займа.
+    pub quantity: Money,
+    /// Оценка задолженности.
+    pub valuation: Money,
+    /// Ставка, % годовых.
+    pub rate: Money,
+    /// Плата за период.
+    pub fee: Money,
+    /// Валюта оценки и платы.
+    pub currency: String,
+}
+
+/// Секция маржинального кредитования.
+#[derive(Debug, Clone)]
+pub struct MarginSection {
+    /// Необеспеченные позиции.
+    pub(crate) rows: Vec<MarginPosition>,
+}
+
+impl MarginSection {
+    /// Создаёт секцию маржинального кредитования.
+    #[must_use]
+    pub const fn new(rows: Vec<MarginPosition>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает необеспеченные позиции.
+    #[must_use]
+    pub fn rows(&self) -> &[MarginPosition] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по необеспеченным позициям.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &MarginPosition> {
+        self.rows.iter()
+    }
+
+    /// Суммирует оценку задолженности по валютам с разбивкой по виду позиции.
+    #[must_use]
+    pub fn exposure_by_currency(&self, kind: MarginExposureKind) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in self.rows.iter().filter(|row| row.kind == kind) {
+            *map.entry(row.currency.clone()).or_insert(Decimal::ZERO) += row.valuation;
+        }
+        map
+    }
+
+    /// Суммирует плату за необеспеченные позиции по валютам.
+    #[must_use]
+    pub fn fees_by_currency(&self) -> BTreeMap<String, Money> {
+        let mut map: BTreeMap<String, Money> = BTreeMap::new();
+        for row in &self.rows {
+            *map.entry(row.currency.clone()).or_insert(Decimal::ZERO) += row.fee;
+        }
+        map
+    }
+}
+
+/// Причина блокировки ценных бумаг.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockReason {
+    /// Блокировка в иностранной инфраструктуре (Euroclear, Clearstream и т. п.).
+    ForeignInfrastructure,
+    /// Залог или иное обременение.
+    Pledge,
+    /// Арест.
+    Arrest,
+    /// Причина не распознана.
+    Unknown,
+}
+
+/// Заблокированная или обременённая позиция по ценной бумаге.
+#[derive(Debug, Clone)]
+pub struct BlockedHolding {
+    /// Наименование бумаги.
+    pub name: String,
+    /// ISIN.
+    pub isin: String,
+    /// Количество, шт.
+    pub quantity: Money,
+    /// Оценка.
+    pub valuation: Money,
+    /// Валюта оценки.
+    pub currency: String,
+    /// Классифицированная причина блокировки.
+    pub reason: BlockReason,
+    /// Исходное описание причины из отчёта.
+    pub reason_raw: String,
+}
+
+/// Таблица заблокированных ценных бумаг.
+#[derive(Debug, Clone)]
+pub struct BlockedHoldingsTable {
+    /// Строки в порядке следования в отчёте.
+    pub(crate) rows: Vec<BlockedHolding>,
+}
+
+impl BlockedHoldingsTable {
+    /// Создаёт таблицу заблокированных ценных бумаг.
+    #[must_use]
+    pub const fn new(rows: Vec<BlockedHolding>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает строки.
+    #[must_use]
+    pub fn rows(&self) -> &[BlockedHolding] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по строкам.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &BlockedHolding> {
+        self.rows.iter()
+    }
+
+    /// Возвращает `true`, если бумага с указанным ISIN заблокирована.
+    #[must_use]
+    pub fn contains_isin(&self, isin: &str) -> bool {
+        self.rows.iter().any(|row| row.isin == isin)
+    }
+
+    /// Возвращает суммарное заблокированное количество бумаги с указанным ISIN.
+    #[must_use]
+    pub fn quantity_for(&self, isin: &str) -> Money {
+        self.rows
+            .iter()
+            .filter(|row| row.isin == isin)
+            .map(|row| row.quantity)
+            .sum()
+    }
+}
+
+/// Направление внебиржевой операции.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OtcDirection {
+    /// Зачисление бумаг на счёт (покупка, перевод от другого брокера).
+    Incoming,
+    /// Списание бумаг со счёта (продажа, перевод другому брокеру).
+    Outgoing,
+    /// Направление не распознано.
+    Unknown,
+}
+
+/// Заявленная стоимость приобретения бумаг по подтверждающим документам.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcquisitionCost {
+    /// Цена приобретения одной бумаги.
+    pub price: Money,
+    /// Общая стоимость приобретения.
+    pub cost: Money,
+    /// Дата приобретения, если указана.
+    pub date: Option<NaiveDate>,
+    /// Валюта стоимости.
+    pub currency: String,
+}
+
+/// Внебиржевая сделка или перевод бумаг между брокерами.
+#[derive(Debug, Clone)]
+pub struct OtcOperation {
+    /// Дата операции.
+    pub date: NaiveDate,
+    /// Наименование бумаги.
+    pub name: String,
+    /// ISIN.
+    pub isin: String,
+    /// Исходное название операции из отчёта.
+    pub operation: String,
+    /// Классификация операции.
+    pub kind: SecurityMovementKind,
+    /// Направление операции.
+    pub direction: OtcDirection,
+    /// Количество, шт.
+    pub quantity: Money,
+    /// Заявленная стоимость приобретения.
+    pub acquisition: AcquisitionCost,
+    /// Контрагент или основание операции.
+    pub counterparty: String,
+}
+
+/// Таблица внебиржевых сделок и переводов.
+#[derive(Debug, Clone)]
+pub struct OtcOperationsTable {
+    /// Операции в порядке следования в отчёте.
+    pub(crate) rows: Vec<OtcOperation>,
+}
+
+impl OtcOperationsTable {
+    /// Создаёт таблицу внебиржевых операций.
+    #[must_use]
+    pub const fn new(rows: Vec<OtcOperation>) -> Self {
+        Self { rows }
+    }
+
+    /// Возвращает операции.
+    #[must_use]
+    pub fn rows(&self) -> &[OtcOperation] {
+        &self.rows
+    }
+
+    /// Возвращает итератор по операциям.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &OtcOperation> {
+        self.rows.iter()
+    }
+
+    /// Сопоставляет строкам движения ЦБ операции по дате, ISIN и количеству.
+    ///
+    /// Каждая операция сопоставляется не более чем одной строке движения — первой
+    /// подходящей по порядку; операции с нераспознанным направлением не сопоставляются.
+    #[must_use]
+    pub fn match_movements(&self, movements: &[SecurityMovement]) -> Vec<Option<&OtcOperation>> {
+        let mut used = vec![false; self.rows.len()];
+        movements
+            .iter()
+            .map(|movement| {
+                let position = self.rows.iter().zip(&used).position(|(row, used)| {
+                    let qty = match row.direction {
+                        OtcDirection::Incoming => movement.qty_in,
+                        OtcDirection::Outgoing => movement.qty_out,
+                        OtcDirection::Unknown => return false,
+                    };
+                    !used
+                        && row.date == movement.date
+                        && row.isin == movement.isin
+                        && row.quantity == qty
+                })?;
+                used[position] = true;
+                Some(&self.rows[position])
+            })
+            .collect()
+    }
+}
+
 /// Итоговая позиция после агрегации нескольких отчётов.
 #[derive(Debug, Clone)]
 pub struct MergedPosition {
@@ -345,16 +1554,18 @@ pub struct MergedPosition {
     pub name: String,
     /// Валюта.
     pub price_currency: String,
+    /// Вид бумаги.
+    pub kind: SecurityKind,
     /// Суммарное количество на начало.
     pub qty_start: Money,
     /// Суммарное количество на конец.
     pub qty_end: Money,
-    /// Стоимость на начало.
+    /// Стоимость на начало (без паёв в реестре владельцев).
     pub value_start_no_ai: Money,
     /// Стоимость на конец.
     pub value_end_no_ai: Money,
     /// Изменение количества.
     pub qty_delta: Money,
-    /// Изменение стоимости.
+    /// Изменение стоимости (без паёв в реестре владельцев).
     pub value_delta: Money,
 }
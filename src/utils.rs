@@ -1,13 +1,12 @@
-//! Вспомогательные парсеры чисел, дат и поиск элементов в HTML.
+//! Вспомогательные парсеры чисел, дат и сбор текста элементов HTML.
 
 use crate::error::ReportError;
 use crate::types::Money;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 use regex::Regex;
 use rust_decimal::Decimal;
-use scraper::{ElementRef, Html, Selector};
+use scraper::ElementRef;
 use std::str::FromStr;
-use std::sync::LazyLock;
 
 /// Нормализует последовательность символов, схлопывая группы пробельных символов.
 fn normalize_chars<I: IntoIterator<Item = char>>(iter: I) -> String {
@@ -57,47 +56,35 @@ pub fn parse_date(value: &str) -> Result<NaiveDate, ReportError> {
     })
 }
 
-/// Собирает текст всех потомков элемента и нормализует пробелы.
-pub fn collect_text(element: ElementRef) -> String {
-    normalize_chars(element.text().flat_map(|s| s.chars()))
+/// Разбирает дату в формате `dd.mm.yyyy`, трактуя пустую ячейку как отсутствие значения.
+pub fn parse_optional_date(value: &str) -> Result<Option<NaiveDate>, ReportError> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_date(value).map(Some)
 }
 
-static TABLE_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("table").expect("valid table selector"));
-static ROW_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("tr").expect("valid tr selector"));
-static HEADER_SELECTOR: LazyLock<Selector> =
-    LazyLock::new(|| Selector::parse("td, th").expect("valid header selector"));
-
-/// Ищет таблицу, в которой одна из первых строк заголовка содержит все требуемые фразы.
-///
-/// Сравнение выполняется по подстроке (`contains`), а по умолчанию проверяется только первая строка
-/// заголовка (`custom_header_depth = 1`).
-pub fn find_table_with_headers<'a>(
-    doc: &'a Html,
-    required_headers: &[&str],
-    custom_header_depth: Option<u8>,
-) -> Option<ElementRef<'a>> {
-    let header_depth = custom_header_depth.unwrap_or(1);
-
-    for table in doc.select(&TABLE_SELECTOR) {
-        let mut rows = table.select(&ROW_SELECTOR);
-        for _ in 0..header_depth {
-            if let Some(header_row) = rows.next() {
-                let headers: Vec<String> = header_row
-                    .select(&HEADER_SELECTOR)
-                    .map(collect_text)
-                    .collect();
-                let matches = required_headers
-                    .iter()
-                    .all(|target| headers.iter().any(|h| h.contains(target)));
-                if matches {
-                    return Some(table);
-                }
-            }
-        }
+/// Разбирает время в формате `hh:mm:ss`, трактуя пустую ячейку как отсутствие значения.
+pub fn parse_optional_time(value: &str) -> Result<Option<NaiveTime>, ReportError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
     }
-    None
+    NaiveTime::parse_from_str(trimmed, "%H:%M:%S")
+        .map(Some)
+        .map_err(|_| ReportError::Date {
+            value: trimmed.to_string(),
+        })
+}
+
+/// Схлопывает группы пробельных символов в строке так же, как [`collect_text`].
+pub fn normalize_whitespace(text: &str) -> String {
+    normalize_chars(text.chars())
+}
+
+/// Собирает текст всех потомков элемента и нормализует пробелы.
+pub fn collect_text(element: ElementRef) -> String {
+    normalize_chars(element.text().flat_map(|s| s.chars()))
 }
 
 /// Находит первый фрагмент текста, совпадающий с регулярным выражением.
@@ -143,6 +130,25 @@ mod tests {
         assert_eq!(normalized, "1234567.89");
     }
 
+    #[test]
+    fn parse_optional_date_handles_empty_and_value() {
+        assert_eq!(parse_optional_date("").expect("empty date"), None);
+        assert_eq!(
+            parse_optional_date("01.02.2025").expect("valid date"),
+            NaiveDate::from_ymd_opt(2025, 2, 1)
+        );
+    }
+
+    #[test]
+    fn parse_optional_time_handles_empty_and_value() {
+        assert_eq!(parse_optional_time("  ").expect("empty time"), None);
+        assert_eq!(
+            parse_optional_time("13:05:09").expect("valid time"),
+            NaiveTime::from_hms_opt(13, 5, 9)
+        );
+        assert!(parse_optional_time("25:00").is_err());
+    }
+
     #[test]
     fn capture_text_returns_first_group() {
         let re = Regex::new(r"Инвестор:\s*(.+)").expect("valid regex");
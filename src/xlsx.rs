@@ -0,0 +1,410 @@
+//! Чтение отчёта брокера, выгруженного в XLSX.
+//!
+//! Листы обходятся по порядку, блоки на листе разделяются пустыми строками: текстовые блоки
+//! становятся абзацами (блок с периодом отчёта — шапкой), блоки из нескольких столбцов —
+//! сетками таблиц ([`TableGrid`]) со строками заголовка, строкой нумерации и развёрнутыми
+//! объединёнными ячейками. Дальше таблицы разбираются теми же парсерами, что и в HTML-отчёте.
+
+use crate::error::ReportError;
+use crate::grid::{ReportTable, SpanCell, TableGrid};
+use crate::raw::RawReport;
+use crate::sources::MAX_ENTRY_SIZE;
+use crate::utils::normalize_whitespace;
+use calamine::{Data, Dimensions, ExcelDateTime, Range, Reader, Xlsx};
+use chrono::NaiveTime;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+/// Содержимое книги: текстовые блоки и таблицы листов в порядке следования.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SheetDocument {
+    blocks: Vec<SheetBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SheetBlock {
+    /// Строки текстового блока; `heading` — шапка отчёта с периодом.
+    Text {
+        lines: Vec<String>,
+        heading: bool,
+    },
+    Table(TableGrid),
+}
+
+impl SheetDocument {
+    /// Текст шапки отчёта: первого блока, в котором указан период.
+    pub(crate) fn heading(&self) -> Option<String> {
+        self.blocks.iter().find_map(|block| match block {
+            SheetBlock::Text {
+                lines,
+                heading: true,
+            } => Some(normalize_whitespace(&lines.join(" "))),
+            _ => None,
+        })
+    }
+
+    /// Тексты остальных блоков, строки разделены переводом строки.
+    pub(crate) fn paragraphs(&self) -> impl Iterator<Item = String> + '_ {
+        self.blocks.iter().filter_map(|block| match block {
+            SheetBlock::Text {
+                lines,
+                heading: false,
+            } => Some(lines.join("\n")),
+            _ => None,
+        })
+    }
+
+    /// Таблицы с заголовками разделов — текстом блока непосредственно перед таблицей.
+    pub(crate) fn tables(&self) -> impl Iterator<Item = ReportTable> + '_ {
+        let mut pending_heading: Option<String> = None;
+        self.blocks.iter().filter_map(move |block| match block {
+            SheetBlock::Text { lines, .. } => {
+                pending_heading = Some(normalize_whitespace(&lines.join(" ")));
+                None
+            }
+            SheetBlock::Table(grid) => Some(ReportTable::new(pending_heading.take(), grid.clone())),
+        })
+    }
+}
+
+/// Ячейка листа с учётом объединений.
+#[derive(Debug, Clone)]
+struct SheetCell {
+    text: String,
+    rowspan: usize,
+    colspan: usize,
+    /// Ячейка закрыта объединённой ячейкой выше или левее.
+    covered: bool,
+}
+
+impl SheetCell {
+    const fn is_anchor(&self) -> bool {
+        !self.covered && (!self.text.is_empty() || self.rowspan > 1 || self.colspan > 1)
+    }
+}
+
+type SheetRow = Vec<SheetCell>;
+
+/// Читает XLSX-книгу в отчёт, который разбирается теми же парсерами таблиц, что и HTML.
+///
+/// Книга с частью больше [`MAX_ENTRY_SIZE`] байт в распакованном виде не читается.
+pub fn read_xlsx<R: Read + Seek>(mut reader: R) -> Result<RawReport, ReportError> {
+    check_part_sizes(&mut reader, MAX_ENTRY_SIZE)?;
+    let mut workbook: Xlsx<R> = Xlsx::new(reader).map_err(workbook_error)?;
+    let mut document = SheetDocument::default();
+
+    for name in workbook.sheet_names() {
+        let range = workbook.worksheet_range(&name).map_err(workbook_error)?;
+        let merges = workbook
+            .worksheet_merge_cells(&name)
+            .transpose()
+            .map_err(workbook_error)?
+            .unwrap_or_default();
+        push_blocks(&mut document.blocks, &sheet_rows(&range, &merges));
+    }
+
+    Ok(RawReport::from_sheets(document))
+}
+
+/// Проверяет по каталогу книги, что ни одна её часть не распаковывается больше чем
+/// в `limit` байт: calamine читает листы в память целиком.
+fn check_part_sizes<R: Read + Seek>(reader: &mut R, limit: u64) -> Result<(), ReportError> {
+    let mut archive = ZipArchive::new(&mut *reader).map_err(workbook_error)?;
+    for index in 0..archive.len() {
+        let part = archive.by_index_raw(index).map_err(workbook_error)?;
+        if part.size() > limit {
+            return Err(ReportError::Workbook(format!(
+                "part '{}' exceeds {limit} bytes",
+                part.name()
+            )));
+        }
+    }
+    drop(archive);
+    reader.rewind()?;
+    Ok(())
+}
+
+/// Раскладывает лист в прямоугольную сетку в пределах заполненного диапазона.
+///
+/// Объединения обрезаются по диапазону, поэтому размер сетки не превышает размер листа,
+/// уже прочитанного в память.
+fn sheet_rows(range: &Range<Data>, merges: &[Dimensions]) -> Vec<SheetRow> {
+    let (Some(start), Some(end)) = (range.start(), range.end()) else {
+        return Vec::new();
+    };
+
+    let mut rows: Vec<SheetRow> = (start.0..=end.0)
+        .map(|row| {
+            (start.1..=end.1)
+                .map(|col| SheetCell {
+                    text: range
+                        .get_value((row, col))
+                        .map(cell_text)
+                        .unwrap_or_default(),
+                    rowspan: 1,
+                    colspan: 1,
+                    covered: false,
+                })
+                .collect()
+        })
+        .collect();
+
+    for merge in merges {
+        let (top, left) = (merge.start.0.max(start.0), merge.start.1.max(start.1));
+        let (bottom, right) = (merge.end.0.min(end.0), merge.end.1.min(end.1));
+        if top > bottom || left > right {
+            continue;
+        }
+        let (top, left) = ((top - start.0) as usize, (left - start.1) as usize);
+        let (bottom, right) = ((bottom - start.0) as usize, (right - start.1) as usize);
+        for (row_idx, row) in rows.iter_mut().enumerate().take(bottom + 1).skip(top) {
+            for (col_idx, cell) in row.iter_mut().enumerate().take(right + 1).skip(left) {
+                cell.covered = (row_idx, col_idx) != (top, left);
+            }
+        }
+        let anchor = &mut rows[top][left];
+        anchor.rowspan = bottom - top + 1;
+        anchor.colspan = right - left + 1;
+    }
+
+    rows
+}
+
+/// Делит лист на блоки: строки из одной ячейки — текст, многостолбцовые — таблицы.
+fn push_blocks(blocks: &mut Vec<SheetBlock>, rows: &[SheetRow]) {
+    let mut start = 0;
+    while start < rows.len() {
+        if is_blank(&rows[start]) {
+            start += 1;
+            continue;
+        }
+        let end = rows[start..]
+            .iter()
+            .position(is_blank)
+            .map_or(rows.len(), |len| start + len);
+        let block = &rows[start..end];
+
+        // Строки из одной ячейки над таблицей — её заголовок.
+        let table_start = block
+            .iter()
+            .position(|row| row.iter().filter(|cell| cell.is_anchor()).count() > 1)
+            .unwrap_or(block.len());
+        if table_start > 0 {
+            blocks.push(text_block(&block[..table_start]));
+        }
+        if table_start < block.len() {
+            blocks.push(SheetBlock::Table(table_grid(&block[table_start..])));
+        }
+        start = end;
+    }
+}
+
+fn text_block(rows: &[SheetRow]) -> SheetBlock {
+    let lines: Vec<String> = rows
+        .iter()
+        .filter_map(|row| row.iter().find(|cell| !cell.text.is_empty()))
+        .map(|cell| cell.text.clone())
+        .collect();
+    // Шапка отчёта с периодом в HTML-выгрузке — заголовок `<h3>`.
+    let heading = lines
+        .iter()
+        .any(|line| line.to_lowercase().contains("за период с"));
+    SheetBlock::Text { lines, heading }
+}
+
+fn table_grid(rows: &[SheetRow]) -> TableGrid {
+    let left = rows
+        .iter()
+        .filter_map(|row| row.iter().position(|cell| cell.is_anchor() || cell.covered))
+        .min()
+        .unwrap_or(0);
+    let right = rows
+        .iter()
+        .filter_map(|row| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| cell.is_anchor())
+                .map(|(idx, cell)| idx + cell.colspan)
+                .max()
+        })
+        .max()
+        .unwrap_or(left);
+
+    // Двухуровневый заголовок выдают объединённые ячейки первой строки.
+    let header_rows = rows[0][left..right]
+        .iter()
+        .filter(|cell| cell.is_anchor())
+        .map(|cell| if cell.colspan > 1 { 2 } else { cell.rowspan })
+        .max()
+        .unwrap_or(1)
+        .min(rows.len());
+    let numbered = rows
+        .get(header_rows)
+        .is_some_and(|row| is_numbering_row(&row[left..right]));
+
+    TableGrid::from_rows(rows.iter().enumerate().map(|(idx, row)| {
+        let class = if idx < header_rows {
+            Some("table-header".to_string())
+        } else if numbered && idx == header_rows {
+            Some("rn".to_string())
+        } else {
+            None
+        };
+        let cells = row[left..right]
+            .iter()
+            .filter(|cell| !cell.covered)
+            .map(|cell| SpanCell {
+                text: normalize_whitespace(&cell.text),
+                rowspan: cell.rowspan,
+                colspan: cell.colspan,
+            })
+            .collect();
+        (class, cells)
+    }))
+}
+
+fn is_blank(row: &SheetRow) -> bool {
+    row.iter().all(|cell| !cell.covered && cell.text.is_empty())
+}
+
+/// Строка нумерации столбцов: `1, 2, 3, …` подряд.
+fn is_numbering_row(cells: &[SheetCell]) -> bool {
+    let numbers: Vec<&str> = cells
+        .iter()
+        .filter(|cell| !cell.covered && !cell.text.is_empty())
+        .map(|cell| cell.text.as_str())
+        .collect();
+    numbers.len() > 1
+        && numbers
+            .iter()
+            .zip(1..)
+            .all(|(text, expected)| text.parse::<usize>() == Ok(expected))
+}
+
+/// Текст ячейки в том виде, в каком он встречается в HTML-отчёте.
+fn cell_text(value: &Data) -> String {
+    match value {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(text) => text.trim().to_string(),
+        Data::Float(number) => number.to_string(),
+        Data::Int(number) => number.to_string(),
+        Data::Bool(flag) => flag.to_string(),
+        Data::DateTime(datetime) => format_datetime(datetime),
+        Data::DateTimeIso(text) | Data::DurationIso(text) => text.clone(),
+    }
+}
+
+fn format_datetime(value: &ExcelDateTime) -> String {
+    let Some(datetime) = value.as_datetime() else {
+        return value.as_f64().to_string();
+    };
+    if value.as_f64() < 1.0 {
+        datetime.format("%H:%M:%S").to_string()
+    } else if datetime.time() == NaiveTime::MIN {
+        datetime.format("%d.%m.%Y").to_string()
+    } else {
+        datetime.format("%d.%m.%Y %H:%M:%S").to_string()
+    }
+}
+
+fn workbook_error(err: impl std::fmt::Display) -> ReportError {
+    ReportError::Workbook(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(texts: &[&str]) -> SheetRow {
+        texts
+            .iter()
+            .map(|text| SheetCell {
+                text: (*text).to_string(),
+                rowspan: 1,
+                colspan: 1,
+                covered: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_sheet_into_text_blocks_and_table_grids() {
+        let mut rows = vec![
+            row(&["Отчет брокера", "", ""]),
+            row(&["за период с 01.01.2025 по 31.01.2025", "", ""]),
+            row(&["", "", ""]),
+            row(&["Оценка активов, руб.", "", ""]),
+            row(&["Площадка", "Начало периода", ""]),
+            row(&["", "ЦБ", "ДС"]),
+            row(&["1", "2", "3"]),
+            row(&["Основной рынок", "10", "5.5"]),
+        ];
+        rows[4][0].rowspan = 2;
+        rows[4][1].colspan = 2;
+        rows[4][2].covered = true;
+        rows[5][0].covered = true;
+
+        let mut document = SheetDocument::default();
+        push_blocks(&mut document.blocks, &rows);
+
+        assert_eq!(
+            document.heading().as_deref(),
+            Some("Отчет брокера за период с 01.01.2025 по 31.01.2025")
+        );
+        assert_eq!(
+            document.paragraphs().collect::<Vec<_>>(),
+            ["Оценка активов, руб."]
+        );
+
+        let tables: Vec<_> = document.tables().collect();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].heading(), Some("Оценка активов, руб."));
+        let grid = tables[0].grid();
+        assert_eq!(grid.header_rows().len(), 2);
+        assert_eq!(grid.body_start(), 3);
+        assert_eq!(
+            grid.header_paths(),
+            [
+                vec!["Площадка".to_string()],
+                vec!["Начало периода".to_string(), "ЦБ".to_string()],
+                vec!["Начало периода".to_string(), "ДС".to_string()],
+            ]
+        );
+        let body: Vec<_> = grid.body_rows().map(|(_, row)| row.cells()).collect();
+        assert_eq!(body, [["Основной рынок", "10", "5.5"]]);
+    }
+
+    #[test]
+    fn clips_sheet_and_merges_to_used_range() {
+        let mut range = Range::new((5, 2), (6, 3));
+        range.set_value((5, 2), Data::String("Площадка".to_string()));
+        range.set_value((6, 3), Data::Float(10.0));
+        let merges = [Dimensions::new((5, 2), (1_000_000, 16_000))];
+
+        let rows = sheet_rows(&range, &merges);
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 2));
+        assert_eq!((rows[0][0].rowspan, rows[0][0].colspan), (2, 2));
+        assert!(rows[1][1].covered);
+    }
+
+    #[test]
+    fn rejects_workbook_parts_over_size_limit() {
+        use std::io::{Cursor, Write};
+        use zip::write::{SimpleFileOptions, ZipWriter};
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("xl/worksheets/sheet1.xml", SimpleFileOptions::default())
+            .expect("start part");
+        writer.write_all(&[b' '; 64]).expect("write part");
+        let mut reader = writer.finish().expect("finish workbook");
+
+        check_part_sizes(&mut reader, 64).expect("fits");
+        assert_eq!(reader.position(), 0);
+        assert!(matches!(
+            check_part_sizes(&mut reader, 63),
+            Err(ReportError::Workbook(message)) if message.contains("sheet1.xml")
+        ));
+    }
+}
@@ -1,7 +1,10 @@
 use rust_decimal::Decimal;
 use sber_invest_report::{
-    CashFlowKind, CashFlowRow, CashFlowSummary, IisLimit, ParseConfig, ParseWarning, Report,
-    ReportBuilder, ReportError, ReportSection, ReportSet, SectionSet,
+    AccountKind, BlockReason, CashFlowKind, CashFlowRow, CashFlowSummary, DomReport,
+    EncodingSource, IisLimit, IncomeKind, IncomeTotals, LoadOptions, MarginExposureKind,
+    OtcDirection, ParseConfig, ParseWarning, Portfolio, PortfolioMarket, PortfolioMarketKind,
+    RepoDirection, Report, ReportBuilder, ReportError, ReportSection, ReportSet, SectionSet,
+    SecurityKind, SecurityMovementKind, SettlementSource, TaxTotals, TradeSide,
 };
 
 fn load_fixture(name: &str) -> Report {
@@ -18,6 +21,32 @@ fn load_raw_fixture(name: &str) -> sber_invest_report::RawReport {
     sber_invest_report::RawReport::from_html(&html)
 }
 
+/// Временный каталог теста; удаляется при выходе из области видимости, в том числе при панике.
+struct TempDir(std::path::PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("sber-report-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("create temp dir");
+        Self(path)
+    }
+
+    fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+
+    fn join(&self, path: &str) -> std::path::PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
 #[test]
 fn parses_broker_fixture() {
     let report = load_fixture("broker_report.html");
@@ -43,6 +72,37 @@ fn parses_iis_fixture() {
     let iis = report.iis_contributions().unwrap();
     assert_eq!(iis.rows().len(), 2);
     assert!(matches!(iis.rows()[0].limit_rub, IisLimit::Unlimited));
+    // Пустые ячейки года и лимита во второй строке наследуются из первой.
+    assert_eq!(iis.rows()[1].year, 2025);
+    assert!(matches!(iis.rows()[1].limit_rub, IisLimit::Unlimited));
+}
+
+#[test]
+fn parses_iis_contributions_with_rowspan_year_and_limit() {
+    let raw = sber_invest_report::RawReport::from_html(
+        r#"<html><body><table>
+            <tr class="table-header">
+                <td>Год</td><td>Лимит, руб.</td><td>Дата операции</td><td>Сумма, руб.</td>
+                <td>Основание операции</td><td>Остаток лимита (сумма к внесению), руб.</td>
+            </tr>
+            <tr class="rn"><td>1</td><td>2</td><td>3</td><td>4</td><td>5</td><td>6</td></tr>
+            <tr><td>ИИС3</td></tr>
+            <tr>
+                <td rowspan="2">2025</td><td rowspan="2">Ограничений нет</td><td>05.02.2025</td>
+                <td>10 000.00</td><td>Зачисление д/с на ИИС</td><td>Ограничений нет</td>
+            </tr>
+            <tr>
+                <td>15.02.2025</td><td>5 000.00</td><td>Зачисление д/с на ИИС</td>
+                <td>Ограничений нет</td>
+            </tr>
+        </table></body></html>"#,
+    );
+    let dom = DomReport::parse(&raw).expect("parse dom");
+    let iis = dom.parse_iis_contributions().expect("iis contributions");
+    assert_eq!(iis.rows().len(), 2);
+    assert_eq!(iis.rows()[1].year, 2025);
+    assert!(matches!(iis.rows()[1].limit_rub, IisLimit::Unlimited));
+    assert_eq!(iis.rows()[1].amount, Decimal::new(5_000, 0));
 }
 
 #[test]
@@ -64,6 +124,701 @@ fn parses_prod_fixture() {
     assert_eq!(markets[0].positions().len(), 3);
 }
 
+#[test]
+fn parses_trades_with_isin_from_reference() {
+    let report = load_fixture("broker_report.html");
+    let trades = report.trades().expect("trades table");
+    assert_eq!(trades.rows().len(), 2);
+
+    let buy = &trades.rows()[0];
+    assert_eq!(buy.venue, "Фондовый рынок");
+    assert_eq!(buy.code, "TEST1");
+    assert_eq!(buy.isin.as_deref(), Some("TESTISIN0001"));
+    assert_eq!(buy.side, TradeSide::Buy);
+    assert_eq!(buy.quantity, Decimal::new(5, 0));
+    assert_eq!(buy.amount, Decimal::new(52500, 2));
+    assert_eq!(buy.broker_fee, Decimal::new(158, 2));
+    assert_eq!(buy.trade_number, "9000000001");
+    assert_eq!(trades.rows()[1].side, TradeSide::Sell);
+}
+
+#[test]
+fn parses_prod_trades_without_reference_match() {
+    let report = load_fixture("prod_data.html");
+    let trades: Vec<_> = report.trade_rows().collect();
+    assert_eq!(trades.len(), 3);
+    assert!(trades.iter().all(|trade| trade.isin.is_none()));
+    assert!(trades.iter().all(|trade| trade.status == "О"));
+    assert_eq!(trades[2].exchange_fee, Decimal::ZERO);
+}
+
+#[test]
+fn parses_cash_operations() {
+    let report = load_fixture("broker_report.html");
+    let operations = report.cash_operations().expect("cash operations table");
+    assert_eq!(operations.rows().len(), 3);
+
+    let dividend = &operations.rows()[1];
+    assert_eq!(dividend.description, "Дивиденды ПАО Тест");
+    assert_eq!(dividend.venue, "Фондовый рынок");
+    assert_eq!(dividend.credit, Decimal::new(87, 0));
+    assert_eq!(dividend.balance, None);
+
+    let net: Decimal = report
+        .cash_operation_rows()
+        .map(sber_invest_report::CashOperation::net)
+        .sum();
+    assert_eq!(net, Decimal::new(150, 0));
+}
+
+#[test]
+fn parses_security_movements_and_explains_qty_delta() {
+    let report = load_fixture("broker_report.html");
+    let movements = report
+        .security_movements()
+        .expect("security movements table");
+    let kinds: Vec<_> = movements.iter_rows().map(|row| row.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            SecurityMovementKind::Trade,
+            SecurityMovementKind::Transfer,
+            SecurityMovementKind::Trade,
+            SecurityMovementKind::Conversion,
+        ]
+    );
+
+    let non_trade = movements.non_trade_net_by_isin();
+    assert_eq!(non_trade.get("TESTISIN0001"), Some(&Decimal::ZERO));
+
+    let position = report.positions().next().expect("position");
+    let movements_net: Decimal = report
+        .security_movement_rows()
+        .filter(|row| row.isin == position.isin)
+        .map(sber_invest_report::SecurityMovement::qty_net)
+        .sum();
+    assert_eq!(movements_net, position.qty_delta);
+}
+
+#[test]
+fn parses_prod_fx_trades_skipping_turnover_rows() {
+    let report = load_fixture("prod_data.html");
+    let fx = report.fx_trades().expect("fx trades table");
+    assert_eq!(fx.rows().len(), 1);
+
+    let deal = &fx.rows()[0];
+    assert_eq!(deal.instrument, "CNYRUB");
+    assert_eq!(deal.pair.to_string(), "CNY/RUB");
+    assert_eq!(deal.side, TradeSide::Buy);
+    assert_eq!(deal.quantity, Decimal::new(200, 0));
+    assert_eq!(deal.rate, Decimal::new(11, 0));
+    assert_eq!(deal.amount, Decimal::new(2200, 0));
+    assert_eq!(deal.broker_fee, Decimal::new(5, 1));
+    assert_eq!(
+        deal.settlement_date,
+        chrono::NaiveDate::from_ymd_opt(2023, 1, 1).unwrap()
+    );
+
+    let cny_rows = report
+        .cash_flow_rows()
+        .filter(|row| row.currency == "CNY")
+        .count();
+    assert!(cny_rows > 0);
+    assert_eq!(fx.iter_for_currency("CNY").count(), 1);
+    assert_eq!(fx.iter_for_currency("USD").count(), 0);
+}
+
+#[test]
+fn parses_security_reference_and_attaches_to_positions() {
+    let report = load_fixture("prod_data.html");
+    let reference = report.security_reference().expect("security reference");
+    assert_eq!(reference.len(), 3);
+
+    let gold = report.security_info("RUGOLD").expect("gold info");
+    assert_eq!(gold.code, "GOLD");
+    assert_eq!(gold.issuer, "Золото");
+    assert_eq!(gold.instrument_type, "Инвестиционный пай");
+    assert_eq!(gold.registration_number, "1");
+    assert_eq!(gold.face_value_currency, None);
+    assert_eq!(
+        reference.by_code("SIL").map(|info| info.isin.as_str()),
+        Some("RUSILVER")
+    );
+    assert_eq!(report.isin_by_code("GOLD"), Some("RUGOLD"));
+    assert_eq!(report.isin_by_code("UNKNOWN"), None);
+
+    assert!(
+        report
+            .positions_with_info()
+            .all(|(position, info)| info.is_some_and(|info| info.isin == position.isin))
+    );
+    let portfolio = report.portfolio().expect("portfolio");
+    let gold_position = portfolio.position("RUGOLD").expect("gold position");
+    assert_eq!(gold_position.isin, "RUGOLD");
+    assert!(
+        portfolio
+            .iter_positions_with_info(reference)
+            .all(|(position, info)| info.is_some_and(|info| info.isin == position.isin))
+    );
+}
+
+#[test]
+fn classifies_positions_without_requesting_reference_section() {
+    let raw = load_raw_fixture("prod_data.html");
+    let config = ParseConfig::lenient()
+        .with_sections(SectionSet::meta_only().with(ReportSection::Portfolio));
+    let (report, warnings) = Report::parse_with_diagnostics(&raw, config).expect("parse portfolio");
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert!(report.security_reference().is_none());
+    assert_eq!(
+        report
+            .portfolio()
+            .unwrap()
+            .position("RUGOLD")
+            .map(|position| position.kind),
+        Some(SecurityKind::FundUnit)
+    );
+}
+
+#[test]
+fn classifies_positions_by_reference_table() {
+    let report = load_fixture("prod_data.html");
+    let kinds: Vec<_> = report
+        .positions()
+        .map(|position| (position.isin.as_str(), position.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("RUGOLD", SecurityKind::FundUnit),
+            ("RUSILVER", SecurityKind::Share),
+            ("RUPLATINUM", SecurityKind::Share),
+        ]
+    );
+
+    let by_kind = report.portfolio().unwrap().value_by_kind();
+    assert_eq!(
+        by_kind.get(&(SecurityKind::FundUnit, "RUB".to_string())),
+        Some(&Decimal::new(190_330, 2))
+    );
+
+    let set = ReportSet::new(vec![report]);
+    assert!(
+        set.merge_positions()
+            .iter()
+            .any(|position| position.kind == SecurityKind::FundUnit)
+    );
+}
+
+#[test]
+fn classification_uses_reference_even_when_section_is_excluded() {
+    let raw = load_raw_fixture("broker_report.html");
+    let config = ParseConfig::default().exclude(ReportSection::SecurityReference);
+    let (report, warnings) =
+        Report::parse_with_diagnostics(&raw, config).expect("parse without reference section");
+    assert!(report.security_reference().is_none());
+    assert_eq!(
+        report.positions().next().map(|position| position.kind),
+        Some(SecurityKind::Share)
+    );
+    assert!(
+        !warnings
+            .iter()
+            .any(|warning| matches!(warning, ParseWarning::AmbiguousSecurityKind { .. }))
+    );
+}
+
+#[test]
+fn parses_income_events_and_aggregates_by_year_and_isin() {
+    let report = load_fixture("broker_report.html");
+    let income = report.income_events().expect("income events table");
+    assert_eq!(income.rows().len(), 2);
+    assert_eq!(income.rows()[0].kind, IncomeKind::Dividend);
+    assert_eq!(income.rows()[1].kind, IncomeKind::Coupon);
+
+    let set = ReportSet::new(vec![report.clone(), report]);
+    let by_year = set.income_by_year();
+    assert_eq!(
+        by_year.get(&(2025, "RUB".to_string())),
+        Some(&IncomeTotals {
+            gross: Decimal::new(27_180, 2),
+            tax_withheld: Decimal::new(3_534, 2),
+            net: Decimal::new(23_646, 2),
+        })
+    );
+
+    let by_isin = set.income_by_isin();
+    assert_eq!(by_isin.len(), 2);
+    assert_eq!(
+        by_isin[&("TESTISIN0001".to_string(), "RUB".to_string())].tax_withheld,
+        Decimal::new(26, 0)
+    );
+}
+
+#[test]
+fn parses_tax_summary_and_aggregates_by_year() {
+    let report = load_fixture("broker_report.html");
+    let tax = report.tax_summary().expect("tax summary");
+    assert_eq!(tax.rows().len(), 2);
+    assert_eq!(tax.rows()[1].income_code, "1010");
+    assert_eq!(tax.rows()[1].rate, Decimal::new(13, 0));
+    assert_eq!(
+        tax.base_by_rate().get(&Decimal::new(13, 0)),
+        Some(&Decimal::new(109, 0))
+    );
+
+    let set = ReportSet::new(vec![report, load_fixture("iis_report.html")]);
+    let by_year = set.tax_by_year();
+    assert_eq!(by_year.len(), 1);
+    assert_eq!(
+        by_year[&2025],
+        TaxTotals {
+            tax_base: Decimal::new(109, 0),
+            calculated: Decimal::new(1_417, 2),
+            withheld: Decimal::new(13, 0),
+            due: Decimal::new(117, 2),
+        }
+    );
+}
+
+#[test]
+fn tax_by_year_takes_latest_report_per_account() {
+    let january = load_raw_fixture("broker_report.html");
+    // Февральский отчёт того же счёта содержит налог нарастающим итогом с начала года.
+    let february = sber_invest_report::RawReport::from_html(
+        &january
+            .html()
+            .expect("html report")
+            .replace(
+                "по 31.01.2025, дата создания 01.02.2025",
+                "по 28.02.2025, дата создания 01.03.2025",
+            )
+            .replace(
+                "<td class=\"c\">1010</td><td>13%</td><td>100.00</td><td>13.00</td><td>13.00</td>",
+                "<td class=\"c\">1010</td><td>13%</td><td>200.00</td><td>26.00</td><td>26.00</td>",
+            ),
+    );
+    let january = Report::parse(&january).expect("parse january");
+    let february = Report::parse(&february).expect("parse february");
+    assert_eq!(
+        february.meta().period_end,
+        chrono::NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()
+    );
+
+    let set = ReportSet::new(vec![february, january.clone(), january]);
+    assert_eq!(
+        set.tax_by_year()[&2025],
+        TaxTotals {
+            tax_base: Decimal::new(209, 0),
+            calculated: Decimal::new(2_717, 2),
+            withheld: Decimal::new(26, 0),
+            due: Decimal::new(117, 2),
+        }
+    );
+}
+
+#[test]
+fn unrecognized_trade_side_and_margin_kind_become_warnings() {
+    let html = load_raw_fixture("broker_report.html")
+        .html()
+        .expect("html report")
+        .replace(
+            "<td class=\"c\">RUB</td><td class=\"c\">Продажа</td>",
+            "<td class=\"c\">RUB</td><td class=\"c\">Мена</td>",
+        )
+        .replace(
+            "<td class=\"c\">Ценные бумаги</td><td>2</td>",
+            "<td class=\"c\">Драгметаллы</td><td>2</td>",
+        );
+    let raw = sber_invest_report::RawReport::from_html(&html);
+
+    let (report, warnings) =
+        Report::parse_with_diagnostics(&raw, ParseConfig::lenient()).expect("lenient parse");
+    let sides: Vec<_> = report
+        .trades()
+        .unwrap()
+        .iter_rows()
+        .map(|trade| trade.side)
+        .collect();
+    assert_eq!(sides, [TradeSide::Buy, TradeSide::Unknown]);
+    assert_eq!(
+        report.margin().unwrap().rows()[1].kind,
+        MarginExposureKind::Unknown
+    );
+    assert!(warnings.iter().any(|warning| matches!(
+        warning,
+        ParseWarning::UnexpectedValue {
+            table: "Trades",
+            column: "Вид",
+            value,
+            ..
+        } if value == "Мена"
+    )));
+    assert!(warnings.iter().any(|warning| matches!(
+        warning,
+        ParseWarning::UnexpectedValue {
+            table: "Margin",
+            column: "Тип задолженности",
+            ..
+        }
+    )));
+
+    let config =
+        ParseConfig::strict().with_sections(SectionSet::meta_only().with(ReportSection::Trades));
+    let err = Report::parse_with_config(&raw, config).expect_err("strict parse must fail");
+    assert!(matches!(
+        err,
+        ReportError::UnexpectedValue {
+            column: "Вид", ..
+        }
+    ));
+}
+
+#[test]
+fn parses_repo_deals_with_both_legs() {
+    let report = load_fixture("broker_report.html");
+    let repo = report.repo_deals().expect("repo deals table");
+    assert_eq!(repo.rows().len(), 2);
+
+    let closed = &repo.rows()[0];
+    assert_eq!(closed.direction, RepoDirection::Reverse);
+    assert_eq!(closed.first_leg.amount, Decimal::new(3_000, 0));
+    assert_eq!(closed.second_leg.amount, Decimal::new(300_250, 2));
+    assert!(closed.second_leg.date.is_some());
+    assert_eq!(closed.rate, Decimal::new(16, 0));
+    assert!(repo.rows()[1].second_leg.date.is_none());
+
+    assert_eq!(
+        report.net_repo_income().get("RUB"),
+        Some(&Decimal::new(250, 2))
+    );
+    assert!(load_fixture("iis_report.html").net_repo_income().is_empty());
+    // Сделки РЕПО не попадают в обычные сделки купли/продажи.
+    assert!(
+        report
+            .trade_rows()
+            .all(|trade| trade.trade_number != closed.trade_number)
+    );
+}
+
+#[test]
+fn parses_derivatives_positions_and_variation_margin() {
+    let report = load_fixture("broker_report.html");
+    let derivatives = report.derivatives().expect("derivatives section");
+    assert_eq!(derivatives.positions().len(), 2);
+
+    let short = &derivatives.positions()[0];
+    assert_eq!(short.contract_code, "SiH5");
+    assert_eq!(short.qty_end, Decimal::new(-2, 0));
+    assert_eq!(
+        derivatives.guarantee_collateral_by_currency().get("RUB"),
+        Some(&Decimal::new(17_000, 0))
+    );
+
+    assert_eq!(report.variation_margin_rows().count(), 3);
+    assert_eq!(
+        derivatives.net_result_by_currency().get("RUB"),
+        Some(&Decimal::new(13_250, 2))
+    );
+    assert!(load_fixture("iis_report.html").derivatives().is_none());
+}
+
+#[test]
+fn parses_pending_settlements_and_projects_cash() {
+    let report = load_fixture("broker_report.html");
+    let pending = report.pending_settlements().expect("pending settlements");
+    assert_eq!(pending.rows().len(), 3);
+
+    let broker = &pending.rows()[0];
+    assert_eq!(broker.source, SettlementSource::BrokerCash);
+    assert_eq!(broker.venue.as_deref(), Some("Основной рынок"));
+    assert_eq!(broker.net(), Decimal::new(324, 0));
+
+    let fx = &pending.rows()[1];
+    assert_eq!(fx.source, SettlementSource::FxMarket);
+    assert_eq!(
+        fx.settlement_date,
+        chrono::NaiveDate::from_ymd_opt(2025, 2, 3)
+    );
+
+    // Рубли берутся из плановых движений брокера и совпадают с его «Плановым исходящим
+    // остатком» 924.00: обязательство валютного рынка по ним уже учтено и не вычитается
+    // повторно. По долларам плановых движений брокера нет, поэтому учитывается требование.
+    let projected = report.projected_cash_by_currency();
+    assert_eq!(projected.get("RUB"), Some(&Decimal::new(924, 0)));
+    assert_eq!(projected.get("USD"), Some(&Decimal::new(10, 0)));
+}
+
+#[test]
+fn parses_pending_fx_obligations_from_real_report() {
+    let raw = load_raw_fixture("prod_data.html");
+    let dom = DomReport::parse(&raw).expect("parse dom");
+    let pending = dom
+        .parse_pending_settlements()
+        .expect("pending settlements");
+    assert!(
+        pending
+            .iter_rows()
+            .all(|row| row.source == SettlementSource::FxMarket)
+    );
+    assert_eq!(
+        pending.net_by_currency().get("CNY"),
+        Some(&Decimal::new(200, 0))
+    );
+}
+
+#[test]
+fn parses_margin_positions_and_flags_leverage() {
+    let report = load_fixture("broker_report.html");
+    let margin = report.margin().expect("margin section");
+    assert_eq!(margin.rows().len(), 2);
+    assert_eq!(
+        margin
+            .exposure_by_currency(MarginExposureKind::BorrowedCash)
+            .get("RUB"),
+        Some(&Decimal::new(1_000, 0))
+    );
+    assert_eq!(
+        margin
+            .exposure_by_currency(MarginExposureKind::ShortSecurity)
+            .get("RUB"),
+        Some(&Decimal::new(216, 0))
+    );
+    assert_eq!(
+        margin.fees_by_currency().get("RUB"),
+        Some(&Decimal::new(618, 2))
+    );
+    assert!(report.uses_leverage());
+    assert!(!report.portfolio().unwrap().has_short_positions());
+    assert!(!load_fixture("iis_report.html").uses_leverage());
+}
+
+#[test]
+fn flags_short_positions_in_portfolio() {
+    let report = load_fixture("broker_report.html");
+    let mut portfolio = report.portfolio().unwrap().clone();
+    let mut short = portfolio.iter_positions().next().unwrap().clone();
+    short.qty_end = Decimal::new(-2, 0);
+    let market = portfolio.iter_markets().next().unwrap().clone();
+    portfolio = Portfolio::new(vec![
+        market,
+        PortfolioMarket::new("Тест".into(), vec![short]),
+    ]);
+
+    assert!(portfolio.has_short_positions());
+    assert_eq!(portfolio.short_positions().count(), 1);
+}
+
+#[test]
+fn parses_blocked_holdings_and_values_with_and_without_them() {
+    let report = load_fixture("broker_report.html");
+    let blocked = report.blocked_holdings().expect("blocked holdings");
+    assert_eq!(blocked.rows().len(), 2);
+    assert!(
+        blocked
+            .iter_rows()
+            .all(|row| row.reason == BlockReason::ForeignInfrastructure)
+    );
+    // Из 12 бумаг позиции заблокированы 3: блокировка частичная.
+    let position = report
+        .portfolio()
+        .unwrap()
+        .position("TESTISIN0001")
+        .unwrap();
+    assert!(position.is_blocked());
+    assert_eq!(position.qty_blocked, Decimal::new(3, 0));
+    assert_eq!(position.unblocked_value_end_no_ai(), Decimal::new(990, 0));
+
+    let with_blocked = report.valuation_by_currency(true);
+    assert_eq!(with_blocked.get("RUB"), Some(&Decimal::new(1_320, 0)));
+    assert_eq!(with_blocked.get("USD"), Some(&Decimal::new(500, 0)));
+    let without_blocked = report.valuation_by_currency(false);
+    assert_eq!(without_blocked.get("RUB"), Some(&Decimal::new(990, 0)));
+    assert_eq!(without_blocked.get("USD"), None);
+
+    // Заблокированное количество переносится и без явного запроса секции.
+    let raw = load_raw_fixture("broker_report.html");
+    let config = ParseConfig::default().exclude(ReportSection::BlockedHoldings);
+    let portfolio_only = Report::parse_with_config(&raw, config).expect("parse portfolio");
+    assert!(portfolio_only.blocked_holdings().is_none());
+    assert!(
+        portfolio_only
+            .positions()
+            .all(|position| position.qty_blocked == Decimal::new(3, 0))
+    );
+}
+
+#[test]
+fn parses_registrar_fund_units_as_separate_market() {
+    let report = load_fixture("iis_report.html");
+    let portfolio = report.portfolio().expect("registrar-only portfolio");
+    assert_eq!(portfolio.markets().len(), 1);
+
+    let market = &portfolio.markets()[0];
+    assert_eq!(market.kind(), PortfolioMarketKind::Registrar);
+    assert!(!market.kind().reports_period_values());
+    let unit = &market.positions()[0];
+    assert_eq!(unit.kind, SecurityKind::FundUnit);
+    assert_eq!(unit.qty_end, Decimal::new(1_225, 2));
+    assert_eq!(unit.price_end, Decimal::new(345_678, 2));
+    assert_eq!(
+        unit.price_date,
+        chrono::NaiveDate::from_ymd_opt(2025, 1, 30)
+    );
+    assert_eq!(
+        report.valuation_by_currency(true).get("RUB"),
+        Some(&Decimal::new(4_234_556, 2))
+    );
+
+    let merged = ReportSet::new(vec![report.clone()]).merge_positions();
+    assert_eq!(merged[0].value_end_no_ai, unit.value_end_no_ai);
+    assert_eq!(merged[0].qty_delta, unit.qty_end - unit.qty_start);
+
+    let broker = load_fixture("broker_report.html");
+    assert!(
+        broker
+            .portfolio()
+            .unwrap()
+            .iter_markets()
+            .all(|market| market.kind() == PortfolioMarketKind::Exchange)
+    );
+}
+
+#[test]
+fn attaches_otc_acquisition_cost_to_security_movements() {
+    let report = load_fixture("broker_report.html");
+    let otc = report.otc_operations().expect("otc operations");
+    assert_eq!(otc.rows().len(), 1);
+    let transfer = &otc.rows()[0];
+    assert_eq!(transfer.kind, SecurityMovementKind::Transfer);
+    assert_eq!(transfer.direction, OtcDirection::Incoming);
+    assert_eq!(
+        transfer.acquisition.date,
+        chrono::NaiveDate::from_ymd_opt(2023, 6, 15)
+    );
+
+    let with_cost: Vec<_> = report
+        .security_movement_rows()
+        .filter_map(|movement| movement.acquisition.as_ref())
+        .collect();
+    assert_eq!(with_cost.len(), 1);
+    assert_eq!(with_cost[0].cost, Decimal::new(95, 0));
+
+    // Стоимость переносится и без явного запроса секции.
+    let raw = load_raw_fixture("broker_report.html");
+    let config = ParseConfig::default().exclude(ReportSection::OtcOperations);
+    let report = Report::parse_with_config(&raw, config).expect("parse without otc");
+    assert!(report.otc_operations().is_none());
+    assert_eq!(
+        report
+            .security_movement_rows()
+            .filter(|movement| movement.acquisition.is_some())
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn matches_each_otc_operation_to_one_movement() {
+    let movement = "<td class=\"c\">16.01.2025</td><td class=\"l\">Фондовый рынок</td><td class=\"l\">Тестовый актив</td><td class=\"c\">TESTISIN0001</td><td class=\"l\">Зачисление</td><td>1</td><td>0</td><td class=\"l\">Перевод от другого брокера</td>";
+    let html = load_raw_fixture("broker_report.html")
+        .html()
+        .expect("html report")
+        .replacen(movement, &format!("{movement}</tr><tr>{movement}"), 1);
+    let report = Report::parse(&sber_invest_report::RawReport::from_html(&html)).expect("parse");
+    let costs: Vec<_> = report
+        .security_movement_rows()
+        .filter(|movement| {
+            movement.date == chrono::NaiveDate::from_ymd_opt(2025, 1, 16).unwrap()
+                && movement.isin == "TESTISIN0001"
+        })
+        .map(|movement| movement.acquisition.is_some())
+        .collect();
+    assert_eq!(costs, [true, false]);
+
+    // Нераспознанный вид операции не прерывает разбор и не сопоставляется с движением.
+    let html = load_raw_fixture("broker_report.html")
+        .html()
+        .expect("html report")
+        .replace(
+            "<td class=\"l\">Зачисление</td><td>1</td><td>95.00</td>",
+            "<td class=\"l\">Конвертация</td><td>1</td><td>95.00</td>",
+        );
+    let (report, warnings) = Report::parse_with_diagnostics(
+        &sber_invest_report::RawReport::from_html(&html),
+        ParseConfig::lenient(),
+    )
+    .expect("lenient parse");
+    assert_eq!(
+        report.otc_operations().unwrap().rows()[0].direction,
+        OtcDirection::Unknown
+    );
+    assert!(warnings.iter().any(|warning| matches!(
+        warning,
+        ParseWarning::UnexpectedValue {
+            table: "OtcOperations",
+            column: "Вид операции",
+            ..
+        }
+    )));
+    assert!(
+        report
+            .security_movement_rows()
+            .all(|movement| movement.acquisition.is_none())
+    );
+}
+
+#[test]
+fn keeps_single_account_report_with_extra_headings_whole() {
+    let raw = load_raw_fixture("broker_report.html");
+    let html = raw.html().expect("html report").replacen(
+        "<p>\n        Сделки купли/продажи ценных бумаг",
+        "<h3>Раздел 2. Операции</h3>\n    <p>\n        Сделки купли/продажи ценных бумаг",
+        1,
+    );
+    assert_ne!(Some(html.as_str()), raw.html());
+    let raw = sber_invest_report::RawReport::from_html(&html);
+    assert_eq!(raw.split_accounts().len(), 1);
+    let reports = Report::parse_all(&raw).expect("parse single report");
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].trades().is_some());
+}
+
+#[test]
+fn splits_combined_report_by_account() {
+    let raw = load_raw_fixture("multi_account/combined_report.html");
+    assert_eq!(raw.split_accounts().len(), 2);
+
+    let reports = Report::parse_all(&raw).expect("parse combined report");
+    let accounts: Vec<_> = reports
+        .iter()
+        .map(|report| {
+            (
+                report.meta().account_id.0.as_str(),
+                report.meta().account_kind,
+            )
+        })
+        .collect();
+    assert_eq!(
+        accounts,
+        [
+            ("200AAA", AccountKind::Broker),
+            ("I300BBB", AccountKind::Iis)
+        ]
+    );
+    assert_eq!(
+        reports[1].cash_flow_rows().next().map(|row| row.amount),
+        Some(Decimal::new(250, 0))
+    );
+
+    let single = load_raw_fixture("broker_report.html");
+    assert_eq!(Report::parse_all(&single).expect("parse single").len(), 1);
+
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multi_account");
+    let set = ReportSet::from_dir(dir).expect("load dir");
+    assert_eq!(set.len(), 2);
+}
+
 #[test]
 fn parse_real_dir_if_present() {
     if let Ok(dir) = std::env::var("REAL_REPORT_DIR") {
@@ -169,3 +924,534 @@ fn parse_with_diagnostics_reports_missing_optional_table() {
         }
     )));
 }
+
+#[test]
+fn fixture_headers_match_expected_columns() {
+    for name in ["broker_report.html", "iis_report.html", "prod_data.html"] {
+        let raw = load_raw_fixture(name);
+        let (_, warnings) = Report::parse_with_diagnostics(&raw, ParseConfig::default())
+            .expect("parse with diagnostics");
+        let column_warnings: Vec<_> = warnings
+            .iter()
+            .filter(|warning| {
+                matches!(
+                    warning,
+                    ParseWarning::MissingColumn { .. } | ParseWarning::UnexpectedColumn { .. }
+                )
+            })
+            .collect();
+        assert!(column_warnings.is_empty(), "{name}: {column_warnings:?}");
+    }
+}
+
+#[test]
+fn maps_columns_by_header_text_when_layout_changes() {
+    let raw = load_raw_fixture("broker_report.html");
+    // Меняем местами «ISIN» и «Вид выплаты» и добавляем новый столбец в конец таблицы.
+    let html = raw
+        .html()
+        .expect("html report")
+        .replace(
+            r#"<td class="c">ISIN ценной бумаги</td><td class="c">Вид выплаты</td>"#,
+            r#"<td class="c">Вид выплаты</td><td class="c">ISIN ценной бумаги</td>"#,
+        )
+        .replace(
+            r#"<td class="c">TESTISIN0001</td><td class="l">Дивиденды</td>"#,
+            r#"<td class="l">Дивиденды</td><td class="c">TESTISIN0001</td>"#,
+        )
+        .replace(
+            r#"<td class="c">Сумма к выплате</td><td class="c">Валюта</td>"#,
+            r#"<td class="c">Сумма к выплате</td><td class="c">Валюта</td><td class="c">Источник</td>"#,
+        );
+    let raw = sber_invest_report::RawReport::from_html(&html);
+    let (report, warnings) = Report::parse_with_diagnostics(&raw, ParseConfig::default())
+        .expect("parse with diagnostics");
+
+    let dividend = report
+        .income_events()
+        .expect("income events")
+        .rows()
+        .iter()
+        .find(|event| event.kind == IncomeKind::Dividend)
+        .expect("dividend row");
+    assert_eq!(dividend.isin, "TESTISIN0001");
+    assert_eq!(dividend.net, Decimal::new(8700, 2));
+    assert!(warnings.iter().any(|warning| matches!(
+        warning,
+        ParseWarning::UnexpectedColumn { table: "IncomeEvents", header } if header == "Источник"
+    )));
+}
+
+#[test]
+fn missing_required_column_skips_section_in_lenient_mode() {
+    let raw = load_raw_fixture("broker_report.html");
+    let html = raw.html().expect("html report").replace(
+        r#"<td class="c">Сумма до налогообложения</td>"#,
+        r#"<td class="c">Доход</td>"#,
+    );
+    let raw = sber_invest_report::RawReport::from_html(&html);
+    let (report, warnings) = Report::parse_with_diagnostics(&raw, ParseConfig::default())
+        .expect("parse with diagnostics");
+
+    assert!(report.income_events().is_none());
+    assert!(warnings.iter().any(|warning| matches!(
+        warning,
+        ParseWarning::MissingColumn {
+            table: "IncomeEvents",
+            column: "Сумма до налогообложения",
+        }
+    )));
+    assert!(warnings.contains(&ParseWarning::SkippedSection {
+        section: ReportSection::IncomeEvents,
+        table: "IncomeEvents",
+        column: "Сумма до налогообложения",
+    }));
+
+    let config = ParseConfig::strict()
+        .with_sections(SectionSet::meta_only().with(ReportSection::IncomeEvents));
+    let err = Report::parse_with_config(&raw, config).unwrap_err();
+    assert!(matches!(err, ReportError::MissingColumn { .. }));
+}
+
+#[test]
+fn table_grid_expands_merged_header_cells() {
+    let raw = load_raw_fixture("broker_report.html");
+    let dom = DomReport::parse(&raw).expect("dom");
+    let grid = dom
+        .table_grid(&["Торговая площадка", "Начало периода", "Конец периода"])
+        .expect("asset valuation grid");
+
+    assert_eq!(grid.header_rows().len(), 2);
+    assert_eq!(grid.width(), 10);
+    let paths = grid.header_paths();
+    assert_eq!(paths[0], ["Торговая площадка"]);
+    assert_eq!(paths[4], ["Конец периода", "Ценные бумаги"]);
+
+    let (idx, row) = grid.body_rows().next().expect("body row");
+    assert_eq!(idx, 3);
+    assert_eq!(row.cell(0), "Основной рынок");
+    assert_eq!(row.cell(6), "1 800.00");
+    assert!(dom.table_grid(&["Нет такой таблицы"]).is_none());
+}
+
+#[test]
+fn lists_raw_tables_with_headings() {
+    let raw = load_raw_fixture("broker_report.html");
+    let dom = DomReport::parse(&raw).expect("dom");
+    let tables: Vec<_> = dom.tables().collect();
+
+    assert_eq!(tables[0].heading(), Some("Оценка активов, руб."));
+    assert!(
+        tables
+            .iter()
+            .any(|table| table.heading() == Some("Справочник Ценных Бумаг"))
+    );
+
+    let margin = dom
+        .find_table(&["Код контракта", "Биржевой сбор"])
+        .expect("variation margin table");
+    assert_eq!(margin.heading(), Some("Вариационная маржа"));
+    assert_eq!(margin.header_rows()[0].cell(3), "Биржевой сбор");
+    let (_, first) = margin.body_rows().next().expect("body row");
+    assert_eq!(first.cells()[..3], ["30.01.2025", "SiH5", "150.00"]);
+}
+
+#[test]
+fn fixtures_have_no_unrecognized_tables() {
+    for name in ["broker_report.html", "iis_report.html", "prod_data.html"] {
+        let raw = load_raw_fixture(name);
+        let (_, warnings) = Report::parse_with_diagnostics(&raw, ParseConfig::default())
+            .expect("parse with diagnostics");
+        let unrecognized: Vec<_> = warnings
+            .iter()
+            .filter(|warning| matches!(warning, ParseWarning::UnrecognizedTable { .. }))
+            .collect();
+        assert!(unrecognized.is_empty(), "{name}: {unrecognized:?}");
+    }
+}
+
+#[test]
+fn reports_unrecognized_table_with_heading_and_headers() {
+    let raw = load_raw_fixture("broker_report.html");
+    let html = raw.html().expect("html report").replacen(
+        "</body>",
+        r#"<p>Структурные продукты</p>
+<table><tr class="table-header"><td>Продукт</td><td>Дата погашения</td></tr>
+<tr><td>Нота</td><td>01.01.2026</td></tr></table></body>"#,
+        1,
+    );
+    let raw = sber_invest_report::RawReport::from_html(&html);
+
+    let (_, warnings) =
+        Report::parse_with_diagnostics(&raw, ParseConfig::default()).expect("parse");
+    let unrecognized = warnings
+        .iter()
+        .find(|warning| matches!(warning, ParseWarning::UnrecognizedTable { .. }))
+        .expect("unrecognized table warning");
+    assert_eq!(
+        unrecognized,
+        &ParseWarning::UnrecognizedTable {
+            heading: Some("Структурные продукты".to_string()),
+            header_cells: vec!["Продукт".to_string(), "Дата погашения".to_string()],
+        }
+    );
+    assert_eq!(unrecognized.heading(), Some("Структурные продукты"));
+    assert_eq!(unrecognized.table(), "");
+}
+
+#[test]
+fn loads_windows_1251_report_with_stale_meta_charset() {
+    let raw = load_raw_fixture("broker_report.html");
+    let expected = Report::parse(&raw).expect("parse utf-8");
+    let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(raw.html().expect("html report"));
+
+    let decoded = sber_invest_report::RawReport::from_reader(bytes.as_ref()).expect("read");
+    assert_eq!(decoded.html(), raw.html());
+    let encoding = decoded.encoding().expect("encoding");
+    assert_eq!(encoding.name, "windows-1251");
+    assert_eq!(encoding.source, EncodingSource::Detected);
+
+    let (report, warnings) =
+        Report::parse_with_diagnostics(&decoded, ParseConfig::default()).expect("parse");
+    assert_eq!(report.meta().investor_name, expected.meta().investor_name);
+    assert_eq!(
+        report
+            .cash_flow_summary()
+            .map(|summary| summary.rows().len()),
+        expected
+            .cash_flow_summary()
+            .map(|summary| summary.rows().len())
+    );
+    assert!(warnings.contains(&ParseWarning::Transcoded { encoding }));
+
+    let explicit = sber_invest_report::RawReport::from_bytes_with_encoding(&bytes, "cp1251")
+        .expect("known label");
+    assert_eq!(explicit.html(), raw.html());
+    assert_eq!(
+        explicit.encoding().map(|encoding| encoding.source),
+        Some(EncodingSource::Explicit)
+    );
+    assert!(matches!(
+        sber_invest_report::RawReport::from_bytes_with_encoding(&bytes, "cp-unknown"),
+        Err(ReportError::UnknownEncoding { .. })
+    ));
+}
+
+#[test]
+fn loads_reports_from_eml_attachments() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/mail/broker_reports.eml");
+    let set = ReportSet::from_file(&path).expect("load eml");
+    let accounts: Vec<_> = set
+        .iter_reports()
+        .map(|report| report.meta().account_id.0.as_str())
+        .collect();
+    // Первое вложение в base64, второе в quoted-printable.
+    assert_eq!(accounts, ["100ABC", "I000XYZ"]);
+}
+
+#[test]
+fn loads_reports_from_zip_archive() {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, source) in [
+        ("2025/02/iis_report.html", "iis_report.html"),
+        ("2025/01/broker_report.html", "broker_report.html"),
+        ("mail/broker_reports.eml", "mail/broker_reports.eml"),
+    ] {
+        archive
+            .start_file(name, SimpleFileOptions::default())
+            .expect("start entry");
+        archive
+            .write_all(&std::fs::read(fixtures.join(source)).expect("read fixture"))
+            .expect("write entry");
+    }
+    archive
+        .start_file("readme.txt", SimpleFileOptions::default())
+        .expect("start entry");
+    archive.write_all(b"not a report").expect("write entry");
+    let bytes = archive.finish().expect("finish archive").into_inner();
+
+    let raws =
+        sber_invest_report::RawReport::from_zip(std::io::Cursor::new(&bytes)).expect("read zip");
+    assert_eq!(raws.len(), 4);
+
+    let dir = TempDir::new("zip");
+    std::fs::write(dir.join("2025.zip"), &bytes).expect("write zip");
+    let accounts: Vec<_> = ReportSet::from_dir(dir.path())
+        .expect("load dir")
+        .iter_reports()
+        .map(|report| report.meta().account_id.0.clone())
+        .collect();
+    assert_eq!(accounts, ["100ABC", "I000XYZ", "100ABC", "I000XYZ"]);
+
+    assert!(matches!(
+        sber_invest_report::RawReport::from_zip(std::io::Cursor::new(b"not a zip")),
+        Err(ReportError::Archive(_))
+    ));
+}
+
+fn zip_archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, bytes) in entries {
+        archive
+            .start_file(*name, SimpleFileOptions::default())
+            .expect("start entry");
+        archive.write_all(bytes).expect("write entry");
+    }
+    archive.finish().expect("finish archive").into_inner()
+}
+
+#[test]
+fn collects_errors_of_unreadable_zip_entries() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let broker = std::fs::read(fixtures.join("broker_report.html")).expect("read fixture");
+    let iis = std::fs::read(fixtures.join("iis_report.html")).expect("read fixture");
+    let bytes = zip_archive(&[
+        ("a/broker_report.html", &broker),
+        ("b/broken.zip", b"not a zip"),
+        ("c/iis_report.html", &iis),
+    ]);
+
+    let dir = TempDir::new("zip-errors");
+    std::fs::write(dir.join("reports.zip"), &bytes).expect("write zip");
+    let strict = ReportSet::from_file(dir.join("reports.zip"));
+    let outcome = ReportSet::load(dir.path(), &LoadOptions::new().with_collect_errors(true))
+        .expect("collect errors");
+
+    assert!(matches!(strict, Err(ReportError::Archive(_))));
+    let accounts: Vec<_> = outcome
+        .reports
+        .iter_reports()
+        .map(|report| report.meta().account_id.0.clone())
+        .collect();
+    assert_eq!(accounts, ["100ABC", "I000XYZ"]);
+    assert_eq!(outcome.errors.len(), 1);
+    assert!(outcome.errors[0].0.ends_with("reports.zip/b/broken.zip"));
+}
+
+#[test]
+fn limits_nesting_depth_of_archives() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut bytes = std::fs::read(fixtures.join("broker_report.html")).expect("read fixture");
+    let mut name = "report.html";
+    for depth in 0..12 {
+        bytes = zip_archive(&[(name, &bytes)]);
+        name = "nested.zip";
+        let result = sber_invest_report::RawReport::from_zip(std::io::Cursor::new(&bytes));
+        // Внешний архив плюс до восьми вложенных.
+        if depth <= 8 {
+            assert_eq!(result.expect("read nested zip").len(), 1);
+        } else {
+            assert!(matches!(result, Err(ReportError::Archive(_))));
+        }
+    }
+}
+
+// Настоящей XLSX-выгрузки брокера в фикстурах нет: книга собрана по `iis_report.html`
+// с той же раскладкой блоков и объединений, поэтому тест проверяет разбор, но не то,
+// что фактический формат брокера совпадает с ней.
+#[test]
+fn parses_xlsx_export_into_same_report() {
+    let html_report = load_fixture("iis_report.html");
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/xlsx/iis_report.xlsx");
+    let raw = sber_invest_report::RawReport::from_xlsx(std::fs::File::open(&path).expect("open"))
+        .expect("read xlsx");
+    assert_eq!(raw.html(), None);
+    let (report, warnings) =
+        Report::parse_with_diagnostics(&raw, ParseConfig::default()).expect("parse xlsx");
+    let unexpected: Vec<_> = warnings
+        .iter()
+        .filter(|warning| !matches!(warning, ParseWarning::MissingTable { .. }))
+        .collect();
+    assert!(unexpected.is_empty(), "{unexpected:?}");
+
+    let (meta, expected_meta) = (report.meta(), html_report.meta());
+    assert_eq!(meta.account_id, expected_meta.account_id);
+    assert_eq!(meta.account_kind, expected_meta.account_kind);
+    assert_eq!(meta.investor_name, expected_meta.investor_name);
+    assert_eq!(
+        (meta.period_start, meta.period_end, meta.generated_at),
+        (
+            expected_meta.period_start,
+            expected_meta.period_end,
+            expected_meta.generated_at
+        )
+    );
+
+    let valuation = |report: &Report| {
+        report
+            .asset_valuation()
+            .expect("asset valuation")
+            .rows()
+            .iter()
+            .map(|row| {
+                (
+                    row.venue.clone(),
+                    row.start_total,
+                    row.end_total,
+                    row.delta_cash,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(valuation(&report), valuation(&html_report));
+
+    let cash_flows = |report: &Report| {
+        report
+            .cash_flow_rows()
+            .map(|row| (row.kind, row.amount, row.currency.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(cash_flows(&report), cash_flows(&html_report));
+
+    let positions = |report: &Report| {
+        report
+            .positions()
+            .map(|position| {
+                (
+                    position.isin.clone(),
+                    position.qty_end,
+                    position.value_end_no_ai,
+                    position.price_date,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert!(!positions(&html_report).is_empty());
+    assert_eq!(positions(&report), positions(&html_report));
+
+    let iis = |report: &Report| {
+        report
+            .iis_contributions()
+            .expect("iis")
+            .rows()
+            .iter()
+            .map(|row| (row.year, row.limit_rub, row.date, row.amount))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(iis(&report), iis(&html_report));
+
+    let set = ReportSet::from_file(&path).expect("load xlsx file");
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn loads_nested_directories_with_filters_and_collected_errors() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let root = TempDir::new("tree");
+    for (target, source) in [
+        ("100ABC/2025/01.html", "broker_report.html"),
+        ("I000XYZ/2025/02.html", "iis_report.html"),
+        ("I000XYZ/2024/12.html", "iis_report.html"),
+        ("drafts/2025/01.html", "broker_report.html"),
+    ] {
+        let path = root.join(target);
+        std::fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+        std::fs::copy(fixtures.join(source), path).expect("copy fixture");
+    }
+    std::fs::write(root.join("100ABC/2025/02.html"), "<html>broken</html>").expect("write");
+
+    let strict = ReportSet::load(root.path(), &LoadOptions::new().with_recursive(true));
+    let top_level = ReportSet::load(root.path(), &LoadOptions::new());
+    let options = LoadOptions::new()
+        .with_recursive(true)
+        .include("*/2025/*.html")
+        .exclude("drafts")
+        .with_collect_errors(true);
+    let outcome = ReportSet::load(root.path(), &options);
+    let invalid = ReportSet::load(root.path(), &LoadOptions::new().include("[broken"));
+
+    assert!(strict.is_err());
+    assert!(top_level.expect("top level").reports.is_empty());
+    assert!(matches!(invalid, Err(ReportError::Pattern(_))));
+
+    let outcome = outcome.expect("collect errors");
+    let accounts: Vec<_> = outcome
+        .reports
+        .iter_reports()
+        .map(|report| report.meta().account_id.0.clone())
+        .collect();
+    assert_eq!(accounts, ["100ABC", "I000XYZ"]);
+    assert!(!outcome.is_complete());
+    assert_eq!(outcome.errors.len(), 1);
+    assert!(outcome.errors[0].0.ends_with("100ABC/2025/02.html"));
+}
+
+#[cfg(unix)]
+#[test]
+fn follows_directory_symlinks_only_when_enabled() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let root = TempDir::new("links");
+    std::os::unix::fs::symlink(fixtures.join("multi_account"), root.join("linked"))
+        .expect("symlink");
+
+    let options = LoadOptions::new().with_recursive(true);
+    let skipped = ReportSet::load(root.path(), &options);
+    let followed = ReportSet::load(root.path(), &options.with_follow_symlinks(true));
+
+    assert!(skipped.expect("skip links").reports.is_empty());
+    assert_eq!(followed.expect("follow links").reports.len(), 2);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_loading_keeps_sequential_order() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let root = TempDir::new("par");
+    for idx in 0..24 {
+        let source = if idx % 3 == 0 {
+            "iis_report.html"
+        } else {
+            "broker_report.html"
+        };
+        std::fs::copy(fixtures.join(source), root.join(&format!("{idx:02}.html")))
+            .expect("copy fixture");
+    }
+    std::fs::write(root.join("07.html"), "<html>broken</html>").expect("write");
+    std::fs::write(root.join("19.html"), "<html>broken</html>").expect("write");
+
+    let options = LoadOptions::new().with_collect_errors(true);
+    let sequential = ReportSet::load(root.path(), &options);
+    let parallel = ReportSet::load_par(root.path(), &options);
+    let strict = ReportSet::load_par(root.path(), &LoadOptions::new());
+
+    let summary = |outcome: &sber_invest_report::LoadOutcome| {
+        let accounts: Vec<_> = outcome
+            .reports
+            .iter_reports()
+            .map(|report| report.meta().account_id.0.clone())
+            .collect();
+        let errors: Vec<_> = outcome
+            .errors
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect();
+        (accounts, errors)
+    };
+    let (sequential, parallel) = (
+        summary(&sequential.expect("sequential")),
+        summary(&parallel.expect("parallel")),
+    );
+    assert_eq!(sequential.0.len(), 22);
+    assert_eq!(parallel, sequential);
+    assert!(strict.is_err());
+
+    let dir = fixtures.join("multi_account");
+    let accounts = |set: ReportSet| {
+        set.iter_reports()
+            .map(|report| report.meta().account_id.0.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        accounts(ReportSet::from_dir_par(&dir).expect("parallel dir")),
+        accounts(ReportSet::from_dir(&dir).expect("sequential dir"))
+    );
+}